@@ -4,6 +4,7 @@ use syn::{parse_macro_input, DeriveInput};
 
 mod borsh;
 mod btr;
+mod rebase;
 
 #[proc_macro_attribute]
 pub fn btr_derive(
@@ -52,3 +53,14 @@ pub fn borsh_serde(
 
     proc_macro::TokenStream::from(expanded)
 }
+
+/// Implements `better_secret_math::traits::Rebase` for a struct with one `#[rebase(elastic)]` and one
+/// `#[rebase(base)]` field, each stored as any type convertible to and from `U256` with `Into`, such as `U256` or
+/// `Uint256`.
+#[proc_macro_derive(Rebase, attributes(rebase))]
+pub fn rebase_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = rebase::derive(input).into_token_stream();
+
+    proc_macro::TokenStream::from(expanded)
+}
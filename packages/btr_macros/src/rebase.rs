@@ -0,0 +1,133 @@
+use syn::{parse_quote, DeriveInput, Field, Ident, ItemImpl};
+
+pub fn derive(input: DeriveInput) -> ItemImpl {
+    let fields = match input.data {
+        syn::Data::Struct(data) => data.fields,
+        _ => panic!("Rebase can only be derived for structs"),
+    };
+
+    let mut elastic = None;
+    let mut base = None;
+    for field in fields.iter() {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path.is_ident("rebase"))
+        {
+            let kind: Ident = attr
+                .parse_args()
+                .expect("expected #[rebase(elastic)] or #[rebase(base)]");
+            let slot = match kind.to_string().as_str() {
+                "elastic" => &mut elastic,
+                "base" => &mut base,
+                _ => panic!("expected #[rebase(elastic)] or #[rebase(base)]"),
+            };
+            if slot.replace(field).is_some() {
+                panic!("#[rebase({})] can only be used once", kind);
+            }
+        }
+    }
+    let elastic = elastic.expect("missing a #[rebase(elastic)] field");
+    let base = base.expect("missing a #[rebase(base)] field");
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let elastic = field_name(elastic);
+    let base = field_name(base);
+
+    // The fields are converted with `Into`, so they can be any type convertible to and from `U256`, such as
+    // `Uint256` under any crate name or alias, and the expansion never names the user's cosmwasm dependency.
+    parse_quote! {
+        impl #impl_generics better_secret_math::traits::Rebase for #ident #ty_generics #where_clause {
+            fn elastic_uint256(&self) -> better_secret_math::traits::Uint256 {
+                ::core::convert::Into::into(self.#elastic)
+            }
+
+            fn base_uint256(&self) -> better_secret_math::traits::Uint256 {
+                ::core::convert::Into::into(self.#base)
+            }
+
+            fn elastic(&self) -> better_secret_math::U256 {
+                ::core::convert::Into::into(self.#elastic)
+            }
+
+            fn base(&self) -> better_secret_math::U256 {
+                ::core::convert::Into::into(self.#base)
+            }
+
+            fn set_elastic(&mut self, value: better_secret_math::U256) {
+                self.#elastic = ::core::convert::Into::into(value);
+            }
+
+            fn set_base(&mut self, value: better_secret_math::U256) {
+                self.#base = ::core::convert::Into::into(value);
+            }
+        }
+    }
+}
+
+fn field_name(field: &Field) -> &Ident {
+    field
+        .ident
+        .as_ref()
+        .expect("Rebase can only be derived for structs with named fields")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebase_fields() {
+        let expanded = derive(parse_quote! {
+            pub struct Pool {
+                #[rebase(elastic)]
+                pub assets: U256,
+                #[rebase(base)]
+                pub shares: secret_cosmwasm_std::Uint256,
+                pub owner: String,
+            }
+        });
+
+        let expected: ItemImpl = parse_quote! {
+            impl better_secret_math::traits::Rebase for Pool {
+                fn elastic_uint256(&self) -> better_secret_math::traits::Uint256 {
+                    ::core::convert::Into::into(self.assets)
+                }
+
+                fn base_uint256(&self) -> better_secret_math::traits::Uint256 {
+                    ::core::convert::Into::into(self.shares)
+                }
+
+                fn elastic(&self) -> better_secret_math::U256 {
+                    ::core::convert::Into::into(self.assets)
+                }
+
+                fn base(&self) -> better_secret_math::U256 {
+                    ::core::convert::Into::into(self.shares)
+                }
+
+                fn set_elastic(&mut self, value: better_secret_math::U256) {
+                    self.assets = ::core::convert::Into::into(value);
+                }
+
+                fn set_base(&mut self, value: better_secret_math::U256) {
+                    self.shares = ::core::convert::Into::into(value);
+                }
+            }
+        };
+
+        assert_eq!(expanded, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "missing a #[rebase(base)] field")]
+    fn missing_base() {
+        derive(parse_quote! {
+            struct Pool {
+                #[rebase(elastic)]
+                assets: U256,
+            }
+        });
+    }
+}
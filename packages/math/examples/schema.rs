@@ -0,0 +1,16 @@
+use std::{env::current_dir, fs};
+
+use better_secret_math::abi::schemas;
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    fs::create_dir_all(&out_dir).unwrap();
+
+    for (name, schema) in schemas() {
+        let path = out_dir.join(format!("{}.json", name));
+        let json = serde_json::to_string_pretty(&schema).unwrap();
+        fs::write(&path, json + "\n").unwrap();
+        println!("Created {}", path.display());
+    }
+}
@@ -0,0 +1,65 @@
+//! JSON schema export for the serializable types in this crate. Only available with the `abi` feature.
+//!
+//! Run `cargo schema` to write the schemas to the `schema` directory. The snapshots there are checked by the tests,
+//! so regenerate them whenever a serialized type changes.
+
+use crate::{
+    common::Rounding,
+    ema::{Ema, EmaMode},
+    lending::{
+        index::Accrual,
+        liquidation::CollateralParams,
+        rate_model::{JumpRateModel, LinearRateModel, SlopeRateModel},
+        scaled::{ScaledBalance, ScaledKind},
+    },
+    oracle::OraclePrice,
+    rewards::{MultiRewardAccumulator, RewardAccumulator, RewardCheckpoint, UserRewards},
+    traits::{
+        PriceSide, RebaseCheckpoint, RebaseHistory, RebaseLedger, SimpleRebase, VirtualRebase,
+    },
+    twap::{Observation, TwapAccumulator, TwapKind, TwapOracle},
+    ud60x18::UD60x18,
+    I256Str, U256Str, I128, I64, U128, U64,
+};
+use schemars::{schema::RootSchema, schema_for};
+
+/// Gets the file name and root schema for every type exported by [crate].
+pub fn schemas() -> Vec<(&'static str, RootSchema)> {
+    vec![
+        ("u64", schema_for!(U64)),
+        ("i64", schema_for!(I64)),
+        ("u128", schema_for!(U128)),
+        ("i128", schema_for!(I128)),
+        ("u256_str", schema_for!(U256Str)),
+        ("i256_str", schema_for!(I256Str)),
+        ("ud60x18", schema_for!(UD60x18)),
+        ("rounding", schema_for!(Rounding)),
+        ("price_side", schema_for!(PriceSide)),
+        ("simple_rebase", schema_for!(SimpleRebase)),
+        ("virtual_rebase", schema_for!(VirtualRebase)),
+        ("rebase_checkpoint", schema_for!(RebaseCheckpoint)),
+        ("rebase_history", schema_for!(RebaseHistory)),
+        ("rebase_ledger", schema_for!(RebaseLedger<String>)),
+        ("oracle_price", schema_for!(OraclePrice)),
+        ("twap_kind", schema_for!(TwapKind)),
+        ("observation", schema_for!(Observation)),
+        ("twap_accumulator", schema_for!(TwapAccumulator)),
+        ("twap_oracle", schema_for!(TwapOracle)),
+        ("ema_mode", schema_for!(EmaMode)),
+        ("ema", schema_for!(Ema)),
+        ("accrual", schema_for!(Accrual)),
+        ("linear_rate_model", schema_for!(LinearRateModel)),
+        ("jump_rate_model", schema_for!(JumpRateModel)),
+        ("slope_rate_model", schema_for!(SlopeRateModel)),
+        ("scaled_kind", schema_for!(ScaledKind)),
+        ("scaled_balance", schema_for!(ScaledBalance)),
+        ("collateral_params", schema_for!(CollateralParams)),
+        ("reward_accumulator", schema_for!(RewardAccumulator)),
+        ("reward_checkpoint", schema_for!(RewardCheckpoint)),
+        (
+            "multi_reward_accumulator",
+            schema_for!(MultiRewardAccumulator<String>),
+        ),
+        ("user_rewards", schema_for!(UserRewards<String>)),
+    ]
+}
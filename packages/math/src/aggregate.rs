@@ -0,0 +1,128 @@
+//! Robust aggregation of values reported by several sources, such as prices from multiple oracles.
+//!
+//! Every function is deterministic for a given multiset of inputs: values are sorted before aggregating, so the order
+//! the sources report in never changes the result.
+
+use crate::{
+    common::{abs_diff, checked_add, muldiv, muldiv_up},
+    ud60x18::{avg, UNIT},
+    U256,
+};
+use cosmwasm_std::{StdError, StdResult};
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum AggregateError {
+    #[error("Aggregation needs at least {required} sources but only {available} are usable")]
+    NotEnoughSources { required: usize, available: usize },
+    #[error("Total weight of the sources is zero")]
+    ZeroWeight,
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<StdError> for AggregateError {
+    fn into(self) -> StdError {
+        StdError::generic_err(self.to_string())
+    }
+}
+
+/// Median of `values`. With an even number of values, the average of the two middle values rounded down.
+pub fn median(values: &[U256]) -> StdResult<U256> {
+    let sorted = sorted(values, 1)?;
+    let mid = sorted.len() / 2;
+    if sorted.len() % 2 == 1 {
+        Ok(sorted[mid])
+    } else {
+        Ok(avg(sorted[mid - 1], sorted[mid]))
+    }
+}
+
+/// Weighted median of `(value, weight)` pairs: the smallest value such that the values up to and including it carry at
+/// least half of the total weight. Ties are broken toward the lower value.
+pub fn weighted_median(values: &[(U256, U256)]) -> StdResult<U256> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let total = sorted
+        .iter()
+        .try_fold(U256::ZERO, |total, (_, weight)| checked_add(total, *weight))?;
+    if total == 0 {
+        return Err(if sorted.is_empty() {
+            not_enough(1, 0)
+        } else {
+            AggregateError::ZeroWeight.into()
+        });
+    }
+
+    // Comparing against the rounded up half keeps an exact half with the lower value.
+    let half = total / 2 + total % 2;
+    let mut cumulative = U256::ZERO;
+    for (value, weight) in sorted {
+        cumulative += weight;
+        if cumulative >= half {
+            return Ok(value);
+        }
+    }
+    unreachable!("the cumulative weight reaches the total")
+}
+
+/// Mean of `values` after dropping the `trim` smallest and `trim` largest, rounded down.
+pub fn trimmed_mean(values: &[U256], trim: usize) -> StdResult<U256> {
+    let sorted = sorted(values, trim.saturating_mul(2).saturating_add(1))?;
+    let kept = &sorted[trim..sorted.len() - trim];
+    // Averaging the offsets from the minimum keeps the sum from overflowing for large values.
+    let min = kept[0];
+    let offsets = kept
+        .iter()
+        .try_fold(U256::ZERO, |total, value| checked_add(total, value - min))?;
+    Ok(min + offsets / U256::from(kept.len() as u128))
+}
+
+/// Keeps the values within `max_deviation` (an unsigned 60.18-decimal fraction) of `reference`, in their original order.
+pub fn filter_max_deviation(
+    values: &[U256],
+    reference: U256,
+    max_deviation: U256,
+) -> StdResult<Vec<U256>> {
+    let max_distance = muldiv(reference, max_deviation, UNIT)?;
+    Ok(values
+        .iter()
+        .copied()
+        .filter(|value| abs_diff(*value, reference) <= max_distance)
+        .collect())
+}
+
+/// Median of the values within `max_deviation` (an unsigned 60.18-decimal fraction) of the median of all `values`,
+/// erroring if fewer than `min_sources` values survive the filter.
+pub fn filtered_median(
+    values: &[U256],
+    max_deviation: U256,
+    min_sources: usize,
+) -> StdResult<U256> {
+    let reference = median(values)?;
+    let survivors = filter_max_deviation(values, reference, max_deviation)?;
+    if survivors.len() < min_sources.max(1) {
+        return Err(not_enough(min_sources.max(1), survivors.len()));
+    }
+    median(&survivors)
+}
+
+/// Deviation of `value` from `reference` as an unsigned 60.18-decimal fraction of `reference`, rounded up.
+pub fn deviation(value: U256, reference: U256) -> StdResult<U256> {
+    muldiv_up(abs_diff(value, reference), UNIT, reference)
+}
+
+fn sorted(values: &[U256], required: usize) -> StdResult<Vec<U256>> {
+    if values.len() < required {
+        return Err(not_enough(required, values.len()));
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    Ok(sorted)
+}
+
+fn not_enough(required: usize, available: usize) -> StdError {
+    AggregateError::NotEnoughSources {
+        required,
+        available,
+    }
+    .into()
+}
@@ -1,21 +1,5 @@
+use crate::convert::{saturating_u512_to_u256, u256_to_u512};
 use ethnum::{I256, U256};
-use primitive_types::{U128, U512};
-
-pub fn u256_to_u512(u: &U256) -> U512 {
-    U512::from_little_endian(&u.to_le_bytes())
-}
-
-pub fn u512_to_u256(r: U512) -> U256 {
-    if r <= U512::zero() {
-        U256::ZERO
-    } else if r > U512::from_little_endian(&U256::MAX.to_le_bytes()) {
-        U256::MAX
-    } else {
-        let lo = U128([r.0[0], r.0[1]]).as_u128();
-        let hi = U128([r.0[2], r.0[3]]).as_u128();
-        U256::from_words(hi, lo)
-    }
-}
 
 /// Assembly math operations.
 /// Mirrors how unchecked arithmetic behaves in Solidity (it uses wrapping arithmetic).
@@ -32,11 +16,11 @@ impl Asm {
             return z % k;
         }
 
-        let x = u256_to_u512(&x);
-        let y = u256_to_u512(&y);
-        let k = u256_to_u512(&k);
+        let x = u256_to_u512(x);
+        let y = u256_to_u512(y);
+        let k = u256_to_u512(k);
         let z = (x + y) % k;
-        u512_to_u256(z)
+        saturating_u512_to_u256(z)
     }
 
     /// Computes (x * y) % k where the addition is performed with arbitrary precision and does not wrap around at 2^256.
@@ -49,11 +33,11 @@ impl Asm {
             return z % k;
         }
 
-        let x = u256_to_u512(&x);
-        let y = u256_to_u512(&y);
-        let k = u256_to_u512(&k);
+        let x = u256_to_u512(x);
+        let y = u256_to_u512(y);
+        let k = u256_to_u512(k);
         let z = (x * y) % k;
-        u512_to_u256(z)
+        saturating_u512_to_u256(z)
     }
 
     /// Compares the first and second operands and returns a value of 1 (true) if the first operand is greater than or equal the second, else a value of 0 (false).
@@ -2,15 +2,20 @@
 //! Forks methods from here - https://github.com/paulrberg/prb-math/blob/main/contracts/PRBMath.sol.
 pub use super::tens::exp10;
 use crate::{
-    asm::{u256_to_u512, u512_to_u256, Asm},
+    asm::Asm,
+    convert::{saturating_u512_to_u256, u256_to_u512},
     ud60x18::constants::*,
 };
+use btr_macros::borsh_serde;
 use cosmwasm_std::{DivideByZeroError, OverflowError, OverflowOperation, StdError, StdResult};
 use primitive_types::U512;
 use std::ops::Not;
 
 use ethnum::U256;
 
+/// Seconds in a 365-day year, used to annualize rates.
+pub const SECONDS_PER_YEAR: u64 = 31_536_000;
+
 /// Finds whether or not some Uint256 is odd.
 pub fn is_odd(x: U256) -> bool {
     x & 1 == 1
@@ -37,6 +42,17 @@ pub fn checked_sub(x: U256, y: U256) -> StdResult<U256> {
     }
 }
 
+pub fn checked_mul(x: U256, y: U256) -> StdResult<U256> {
+    let (a, b) = x.overflowing_mul(y);
+    if b {
+        Err(StdError::Overflow {
+            source: OverflowError::new(OverflowOperation::Mul, x, y),
+        })
+    } else {
+        Ok(a)
+    }
+}
+
 /// Takes the absolute difference of two unsigned ints.
 pub fn abs_diff(x: U256, y: U256) -> U256 {
     if x > y {
@@ -61,6 +77,51 @@ pub fn bankers_round(x: U256, digit: u8) -> U256 {
     (x + if round_up { precision } else { U256::ZERO }) / precision * precision
 }
 
+/// Rounding strategy used when a result cannot be represented exactly.
+///
+/// Rounding is always applied to the magnitude of a value, so `Up` rounds a negative number away from zero.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub enum Rounding {
+    /// Round toward zero (truncate).
+    Down,
+    /// Round away from zero.
+    Up,
+    /// Round to the nearest value, ties away from zero.
+    HalfUp,
+    /// Round to the nearest value, ties to the nearest even value (bankers rounding).
+    HalfEven,
+}
+
+impl Rounding {
+    /// Whether the truncated quotient `q` of some division by `divisor` that left `remainder` should be increased by one.
+    pub fn rounds_up(self, q: U256, remainder: U256, divisor: U256) -> bool {
+        if remainder == 0 {
+            return false;
+        }
+        // Comparing against `divisor - remainder` avoids overflowing `2 * remainder`.
+        let rest = divisor - remainder;
+        match self {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfUp => remainder >= rest,
+            Rounding::HalfEven => remainder > rest || (remainder == rest && is_odd(q)),
+        }
+    }
+}
+
+/// Maps `round_up` flags onto [Rounding::Up] and [Rounding::Down].
+impl From<bool> for Rounding {
+    fn from(round_up: bool) -> Self {
+        if round_up {
+            Rounding::Up
+        } else {
+            Rounding::Down
+        }
+    }
+}
+
 /// Where x is a positive integer. Supports up to 32 digits.
 pub fn nth_digit(x: U256, digit: u8) -> u8 {
     ((x / exp10(digit - 1)) % 10).as_u8()
@@ -124,6 +185,43 @@ pub fn msb(mut x: U256) -> U256 {
 /// @param denominator The divisor as an uint256.
 /// @return result The result as an uint256.
 pub fn muldiv(x: U256, y: U256, denominator: U256) -> StdResult<U256> {
+    let (prod0, prod1) = muldiv_product(x, y, denominator)?;
+
+    // Handle non-overflow cases, 256 by 256 division.
+    if prod1 == 0 {
+        return Ok(prod0 / denominator);
+    }
+
+    // Can't saturate since prod1 < denominator guarantees the quotient fits.
+    Ok(saturating_u512_to_u256(
+        wide_product(prod0, prod1) / u256_to_u512(denominator),
+    ))
+}
+
+/// Calculates floor(x*y÷denominator) with full precision and the remainder (x*y) mod denominator, from a single
+/// 512-bit product.
+///
+/// Requirements:
+/// - All from "muldiv".
+pub fn muldiv_rem(x: U256, y: U256, denominator: U256) -> StdResult<(U256, U256)> {
+    let (prod0, prod1) = muldiv_product(x, y, denominator)?;
+
+    // Handle non-overflow cases, 256 by 256 division.
+    if prod1 == 0 {
+        return Ok((prod0 / denominator, prod0 % denominator));
+    }
+
+    let (quotient, remainder) = wide_product(prod0, prod1).div_mod(u256_to_u512(denominator));
+    // Can't saturate since prod1 < denominator guarantees the quotient fits, and the remainder is below denominator.
+    Ok((
+        saturating_u512_to_u256(quotient),
+        saturating_u512_to_u256(remainder),
+    ))
+}
+
+/// The 512-bit product x*y as its least and most significant 256 bits, erroring if the denominator is zero or
+/// x*y÷denominator does not fit within uint256.
+fn muldiv_product(x: U256, y: U256, denominator: U256) -> StdResult<(U256, U256)> {
     if denominator == 0 {
         return Err(StdError::DivideByZero {
             source: DivideByZeroError {
@@ -137,19 +235,11 @@ pub fn muldiv(x: U256, y: U256, denominator: U256) -> StdResult<U256> {
     // variables such that product = prod1 * 2^256 + prod0.
     // Least significant 256 bits of the product
     // Most significant 256 bits of the product
-    let result: U256;
-
     let mm = Asm::mulmod(x, y, U256::ZERO.not());
     let prod0: U256 = Asm::mul(x, y);
     let prod1: U256 = Asm::u_sub(Asm::u_sub(mm, prod0), Asm::lt(mm, prod0));
 
-    // Handle non-overflow cases, 256 by 256 division.
-    if prod1 == 0 {
-        result = prod0 / denominator;
-        return Ok(result);
-    }
-
-    // Make sure the result is less than 2^256. Also prevents denominator == 0.
+    // Make sure the result is less than 2^256.
     if prod1 >= denominator {
         return Err(StdError::Overflow {
             source: OverflowError {
@@ -159,18 +249,38 @@ pub fn muldiv(x: U256, y: U256, denominator: U256) -> StdResult<U256> {
             },
         });
     }
+    Ok((prod0, prod1))
+}
 
-    ///////////////////////////////////////////////
-    // 512 by 256 division.
-    ///////////////////////////////////////////////
-
+/// Joins the halves of a 512-bit product from [muldiv_product].
+fn wide_product(prod0: U256, prod1: U256) -> U512 {
     let lo = prod0.to_le_bytes();
     let hi = prod1.to_le_bytes();
     let lo_hi = [lo, hi].concat();
-    let xy = U512::from_little_endian(&lo_hi);
-    let denominator = u256_to_u512(&denominator);
-    result = u512_to_u256(&xy / denominator);
-    Ok(result)
+    U512::from_little_endian(&lo_hi)
+}
+
+/// Calculates ceil(x*y÷denominator) with full precision.
+///
+/// Requirements:
+/// - All from "muldiv".
+pub fn muldiv_up(x: U256, y: U256, denominator: U256) -> StdResult<U256> {
+    muldiv_rounded(x, y, denominator, Rounding::Up)
+}
+
+/// Calculates x*y÷denominator with full precision, rounding the result with `rounding`.
+///
+/// The rounding direction is decided from the remainder left by "muldiv_rem", so the product is only computed once.
+///
+/// Requirements:
+/// - All from "muldiv".
+pub fn muldiv_rounded(x: U256, y: U256, denominator: U256, rounding: Rounding) -> StdResult<U256> {
+    let (result, remainder) = muldiv_rem(x, y, denominator)?;
+    if rounding.rounds_up(result, remainder, denominator) {
+        checked_add(result, U256::ONE)
+    } else {
+        Ok(result)
+    }
 }
 
 /// @notice Calculates floor(x*y÷1e18) with full precision.
@@ -606,6 +716,56 @@ mod test {
         assert_eq!(muldiv(x, y, denom).unwrap(), expected);
     }
 
+    #[rstest]
+    #[case("7", "3", "2", "10", "1")]
+    #[case("19318389123", "1319320194941", "219031831291", "116362725698", "57207810625")]
+    #[case(U256::MAX, "3", U256::MAX - 1, "3", "3")]
+    #[case(U256::MAX - 1, U256::MAX - 1, U256::MAX, U256::MAX - 2, "1")]
+    fn test_muldiv_rem(
+        #[case] x: U256,
+        #[case] y: U256,
+        #[case] denom: U256,
+        #[case] quotient: U256,
+        #[case] remainder: U256,
+    ) {
+        assert_eq!(muldiv_rem(x, y, denom).unwrap(), (quotient, remainder));
+    }
+
+    #[rstest]
+    #[case("7", "3", "7", "3")]
+    #[case("7", "3", "2", "11")]
+    #[case("0", "3", "2", "0")]
+    #[case(U256::MAX, U256::MAX, U256::MAX, U256::MAX)]
+    #[case(U256::MAX, "2", U256::MAX - 1, "3")]
+    fn test_muldiv_up(
+        #[case] x: U256,
+        #[case] y: U256,
+        #[case] denom: U256,
+        #[case] expected: U256,
+    ) {
+        assert_eq!(muldiv_up(x, y, denom).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("7", "3", "2", Rounding::Down, "10")]
+    #[case("7", "3", "2", Rounding::Up, "11")]
+    #[case("7", "3", "2", Rounding::HalfUp, "11")]
+    #[case("7", "3", "2", Rounding::HalfEven, "10")]
+    #[case("5", "3", "2", Rounding::HalfEven, "8")]
+    #[case("7", "1", "3", Rounding::HalfUp, "2")]
+    #[case("8", "1", "3", Rounding::HalfUp, "3")]
+    #[case("6", "1", "3", Rounding::Up, "2")]
+    #[case(U256::MAX, U256::MAX, U256::MAX, Rounding::Up, U256::MAX)]
+    fn test_muldiv_rounded(
+        #[case] x: U256,
+        #[case] y: U256,
+        #[case] denom: U256,
+        #[case] rounding: Rounding,
+        #[case] expected: U256,
+    ) {
+        assert_eq!(muldiv_rounded(x, y, denom, rounding).unwrap(), expected);
+    }
+
     #[rstest]
     #[case("12443", "443", "12000", "12886")]
     fn test_checked_ok(#[case] x: U256, #[case] y: U256, #[case] xsuby: U256, #[case] xaddy: U256) {
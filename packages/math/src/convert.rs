@@ -0,0 +1,203 @@
+//! Conversions between [U256] and the other wide integer types used alongside it, namely [primitive_types::U256],
+//! [U512] and [Uint512], and between those types directly.
+//!
+//! Conversions into a wider type are lossless. Conversions into a narrower type come in a checked flavor that errors
+//! with [StdError::ConversionOverflow] and a saturating flavor that clamps to [U256::MAX].
+
+use cosmwasm_std::{ConversionOverflowError, StdError, StdResult, Uint512};
+use ethnum::U256;
+use primitive_types::U512;
+
+/// Converts a [U256] into a [primitive_types::U256].
+pub fn u256_to_primitive(x: U256) -> primitive_types::U256 {
+    primitive_types::U256::from_little_endian(&x.to_le_bytes())
+}
+
+/// Converts a [primitive_types::U256] into a [U256].
+pub fn primitive_to_u256(x: primitive_types::U256) -> U256 {
+    let mut bytes = [0u8; 32];
+    x.to_little_endian(&mut bytes);
+    U256::from_le_bytes(bytes)
+}
+
+/// Converts a [U256] into a [U512].
+pub fn u256_to_u512(x: U256) -> U512 {
+    U512::from_little_endian(&x.to_le_bytes())
+}
+
+/// Converts a [U512] into a [U256], erroring if it does not fit.
+pub fn checked_u512_to_u256(x: U512) -> StdResult<U256> {
+    u512_low(x).ok_or_else(|| overflow("U512", "U256", x.to_string()))
+}
+
+/// Converts a [U512] into a [U256], saturating at [U256::MAX].
+pub fn saturating_u512_to_u256(x: U512) -> U256 {
+    u512_low(x).unwrap_or(U256::MAX)
+}
+
+/// Converts a [U256] into a [Uint512].
+pub fn u256_to_uint512(x: U256) -> Uint512 {
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&x.to_le_bytes());
+    Uint512::from_le_bytes(bytes)
+}
+
+/// Converts a [Uint512] into a [U256], erroring if it does not fit.
+pub fn checked_uint512_to_u256(x: Uint512) -> StdResult<U256> {
+    uint512_low(x).ok_or_else(|| overflow("Uint512", "U256", x.to_string()))
+}
+
+/// Converts a [Uint512] into a [U256], saturating at [U256::MAX].
+pub fn saturating_uint512_to_u256(x: Uint512) -> U256 {
+    uint512_low(x).unwrap_or(U256::MAX)
+}
+
+/// Converts a [U512] into a [Uint512].
+pub fn u512_to_uint512(x: U512) -> Uint512 {
+    let mut bytes = [0u8; 64];
+    x.to_little_endian(&mut bytes);
+    Uint512::from_le_bytes(bytes)
+}
+
+/// Converts a [Uint512] into a [U512].
+pub fn uint512_to_u512(x: Uint512) -> U512 {
+    U512::from_little_endian(&x.to_le_bytes())
+}
+
+/// Converts a [primitive_types::U256] into a [U512].
+pub fn primitive_to_u512(x: primitive_types::U256) -> U512 {
+    U512::from(x)
+}
+
+/// Converts a [U512] into a [primitive_types::U256], erroring if it does not fit.
+pub fn checked_u512_to_primitive(x: U512) -> StdResult<primitive_types::U256> {
+    primitive_types::U256::try_from(x).map_err(|_| overflow("U512", PRIMITIVE_U256, x.to_string()))
+}
+
+/// Converts a [U512] into a [primitive_types::U256], saturating at [primitive_types::U256::MAX].
+pub fn saturating_u512_to_primitive(x: U512) -> primitive_types::U256 {
+    primitive_types::U256::try_from(x).unwrap_or(primitive_types::U256::MAX)
+}
+
+/// Converts a [primitive_types::U256] into a [Uint512].
+pub fn primitive_to_uint512(x: primitive_types::U256) -> Uint512 {
+    u512_to_uint512(primitive_to_u512(x))
+}
+
+/// Converts a [Uint512] into a [primitive_types::U256], erroring if it does not fit.
+pub fn checked_uint512_to_primitive(x: Uint512) -> StdResult<primitive_types::U256> {
+    checked_u512_to_primitive(uint512_to_u512(x))
+        .map_err(|_| overflow("Uint512", PRIMITIVE_U256, x.to_string()))
+}
+
+/// Converts a [Uint512] into a [primitive_types::U256], saturating at [primitive_types::U256::MAX].
+pub fn saturating_uint512_to_primitive(x: Uint512) -> primitive_types::U256 {
+    saturating_u512_to_primitive(uint512_to_u512(x))
+}
+
+/// Gets x as a [U256] if its upper 256 bits are zero.
+fn u512_low(x: U512) -> Option<U256> {
+    primitive_types::U256::try_from(x)
+        .ok()
+        .map(primitive_to_u256)
+}
+
+/// Gets x as a [U256] if its upper 256 bits are zero.
+fn uint512_low(x: Uint512) -> Option<U256> {
+    let bytes = x.to_le_bytes();
+    if bytes[32..].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut lo = [0u8; 32];
+    lo.copy_from_slice(&bytes[..32]);
+    Some(U256::from_le_bytes(lo))
+}
+
+/// Type name of [primitive_types::U256] in conversion errors, to tell it apart from [U256].
+const PRIMITIVE_U256: &str = "primitive_types::U256";
+
+fn overflow(source_type: &'static str, target_type: &'static str, value: String) -> StdError {
+    StdError::ConversionOverflow {
+        source: ConversionOverflowError::new(source_type, target_type, value),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use cosmwasm_std::Uint256;
+    use rstest::*;
+
+    #[rstest]
+    #[case(U256::ZERO)]
+    #[case(U256::ONE)]
+    #[case(U256::from_words(1, 0))]
+    #[case(U256::from_words(
+        0x0123456789abcdef0123456789abcdef,
+        0xfedcba9876543210fedcba9876543210
+    ))]
+    #[case(U256::MAX)]
+    fn test_lossless(#[case] x: U256) {
+        let primitive = u256_to_primitive(x);
+        assert_eq!(primitive.to_string(), x.to_string());
+        assert_eq!(primitive_to_u256(primitive), x);
+
+        let wide = u256_to_u512(x);
+        assert_eq!(wide.to_string(), x.to_string());
+        assert_eq!(checked_u512_to_u256(wide).unwrap(), x);
+        assert_eq!(saturating_u512_to_u256(wide), x);
+
+        let uint = u256_to_uint512(x);
+        assert_eq!(uint, Uint512::from(Uint256::from(x)));
+        assert_eq!(checked_uint512_to_u256(uint).unwrap(), x);
+        assert_eq!(saturating_uint512_to_u256(uint), x);
+
+        assert_eq!(u512_to_uint512(wide), uint);
+        assert_eq!(uint512_to_u512(uint), wide);
+
+        assert_eq!(primitive_to_u512(primitive), wide);
+        assert_eq!(checked_u512_to_primitive(wide).unwrap(), primitive);
+        assert_eq!(saturating_u512_to_primitive(wide), primitive);
+        assert_eq!(primitive_to_uint512(primitive), uint);
+        assert_eq!(checked_uint512_to_primitive(uint).unwrap(), primitive);
+        assert_eq!(saturating_uint512_to_primitive(uint), primitive);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let wide = u256_to_u512(U256::MAX) + 1;
+        assert!(matches!(
+            checked_u512_to_u256(wide),
+            Err(StdError::ConversionOverflow { source }) if source.target_type == "U256"
+        ));
+        assert_eq!(saturating_u512_to_u256(wide), U256::MAX);
+        assert_eq!(saturating_u512_to_u256(U512::MAX), U256::MAX);
+
+        let uint = u512_to_uint512(wide);
+        assert!(matches!(
+            checked_uint512_to_u256(uint),
+            Err(StdError::ConversionOverflow { .. })
+        ));
+        assert_eq!(saturating_uint512_to_u256(uint), U256::MAX);
+        assert_eq!(saturating_uint512_to_u256(Uint512::MAX), U256::MAX);
+        assert_eq!(uint512_to_u512(Uint512::MAX), U512::MAX);
+
+        assert!(matches!(
+            checked_u512_to_primitive(wide),
+            Err(StdError::ConversionOverflow { source }) if source.target_type == PRIMITIVE_U256
+        ));
+        assert_eq!(
+            saturating_u512_to_primitive(U512::MAX),
+            primitive_types::U256::MAX
+        );
+        assert!(matches!(
+            checked_uint512_to_primitive(uint),
+            Err(StdError::ConversionOverflow { source })
+                if source.source_type == "Uint512" && source.target_type == PRIMITIVE_U256
+        ));
+        assert_eq!(
+            saturating_uint512_to_primitive(Uint512::MAX),
+            primitive_types::U256::MAX
+        );
+    }
+}
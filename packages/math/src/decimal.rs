@@ -0,0 +1,138 @@
+//! Conversions between base-10 strings such as `"1.25"` or `"-3e-4"` and 18-decimal fixed-point numbers.
+//! Shared by [crate::ud60x18] and [crate::sd59x18], which wrap these helpers with their own range checks.
+
+use crate::{
+    common::{exp10, is_odd, Rounding},
+    ud60x18::constants::UNIT,
+};
+use cosmwasm_std::StdError;
+use ethnum::U256;
+
+/// Number of decimals in the fixed-point representation.
+const DECIMALS: usize = 18;
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum DecimalError {
+    #[error("Invalid decimal string: {0:?}")]
+    Invalid(String),
+    #[error("Decimal {0:?} has more than 18 decimals")]
+    ExcessPrecision(String),
+    #[error("Decimal {0:?} is out of range")]
+    Overflow(String),
+    #[error("Decimal {0:?} is negative")]
+    Negative(String),
+}
+
+#[allow(clippy::from_over_into)]
+impl Into<StdError> for DecimalError {
+    fn into(self) -> StdError {
+        StdError::parse_err("decimal", self.to_string())
+    }
+}
+
+/// Parses a decimal string into its sign and the magnitude scaled by 1e18.
+///
+/// Accepts an optional sign, an integer and/or fractional part, and an optional exponent (`1.5e-3`, `2E+4`).
+/// Digits beyond the 18th decimal are rejected when `rounding` is `None`, otherwise they are rounded away.
+pub(crate) fn parse(s: &str, rounding: Option<Rounding>) -> Result<(bool, U256), DecimalError> {
+    let invalid = || DecimalError::Invalid(s.to_string());
+
+    let (negative, unsigned) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(i) => {
+            let exponent = &unsigned[i + 1..];
+            let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+            if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            (
+                &unsigned[..i],
+                exponent.parse::<i64>().map_err(|_| invalid())?,
+            )
+        }
+        None => (unsigned, 0),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if int_part.is_empty() && frac_part.is_empty() {
+        return Err(invalid());
+    }
+    if !int_part
+        .bytes()
+        .chain(frac_part.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return Err(invalid());
+    }
+
+    // The value is `digits * 10^scale`.
+    let digits: Vec<u8> = int_part
+        .bytes()
+        .chain(frac_part.bytes())
+        .map(|b| b - b'0')
+        .skip_while(|d| *d == 0)
+        .collect();
+    let scale = (DECIMALS as i64 - frac_part.len() as i64)
+        .checked_add(exponent)
+        .ok_or_else(invalid)?;
+
+    let (kept, dropped) = if scale >= 0 {
+        (&digits[..], &[][..])
+    } else {
+        let cut = digits.len().saturating_sub(scale.unsigned_abs() as usize);
+        digits.split_at(cut)
+    };
+
+    let overflow = || DecimalError::Overflow(s.to_string());
+    let mut magnitude = U256::ZERO;
+    for digit in kept {
+        magnitude = magnitude
+            .checked_mul(U256::new(10))
+            .and_then(|m| m.checked_add(U256::from(*digit)))
+            .ok_or_else(overflow)?;
+    }
+    if scale > 0 && magnitude != 0 {
+        if scale > 77 {
+            return Err(overflow());
+        }
+        magnitude = magnitude
+            .checked_mul(exp10(scale as u8))
+            .ok_or_else(overflow)?;
+    }
+
+    if dropped.iter().any(|d| *d != 0) {
+        let rounding = rounding.ok_or_else(|| DecimalError::ExcessPrecision(s.to_string()))?;
+        // When more digits were cut than were written, the cut part starts with implicit zeros and is below half.
+        let padded = scale.unsigned_abs() as usize > dropped.len();
+        let first = if padded { 0 } else { dropped[0] };
+        let round_up = match rounding {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfUp => first >= 5,
+            Rounding::HalfEven => {
+                first > 5
+                    || (first == 5 && (dropped[1..].iter().any(|d| *d != 0) || is_odd(magnitude)))
+            }
+        };
+        if round_up {
+            magnitude = magnitude.checked_add(U256::ONE).ok_or_else(overflow)?;
+        }
+    }
+
+    Ok((negative, magnitude))
+}
+
+/// Formats the magnitude of an 18-decimal fixed-point number, keeping at most `max_decimals` decimals (extra
+/// decimals are truncated) and trimming trailing zeros.
+pub(crate) fn format(magnitude: U256, max_decimals: u8) -> String {
+    let frac = format!("{:0>18}", (magnitude % UNIT).to_string());
+    let frac = frac[..DECIMALS.min(max_decimals.into())].trim_end_matches('0');
+    if frac.is_empty() {
+        (magnitude / UNIT).to_string()
+    } else {
+        format!("{}.{}", magnitude / UNIT, frac)
+    }
+}
@@ -0,0 +1,92 @@
+//! Exponential moving averages that decay with elapsed time, for smoothing prices or utilization that are sampled at
+//! irregular intervals.
+//!
+//! Each update moves the average toward the new value by `alpha = 1 - exp(-dt / tau)`, so a value observed for `tau`
+//! seconds carries about 63% of the weight no matter how often the average is updated.
+
+use crate::{
+    common::{muldiv, muldiv18},
+    ud60x18::{self, UNIT, UNIT_SQUARED},
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// `dt / tau` from which `exp(-dt / tau)` rounds to zero in 60.18 decimals, so alpha is one.
+const FULL_DECAY: U256 = U256::new(42 * UNIT.as_u128());
+
+/// Largest `dt / tau` for which [EmaMode::Taylor] uses the series, 0.1 in 60.18 decimals. Its relative error there is
+/// under `0.1^4 / 24`, about 4e-6.
+pub const TAYLOR_MAX_RATIO: U256 = U256::new(UNIT.as_u128() / 10);
+
+/// How [Ema] computes the smoothing factor.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub enum EmaMode {
+    /// `1 - exp(-dt / tau)` with [ud60x18::exp].
+    Exact,
+    /// The third-order Taylor series `x - x^2 / 2 + x^3 / 6` of `1 - exp(-x)`, which is cheaper for small `dt`. Falls
+    /// back to [EmaMode::Exact] when `dt / tau` exceeds [TAYLOR_MAX_RATIO].
+    Taylor,
+}
+
+/// A time-decayed exponential moving average of unsigned values, e.g. prices or utilization in 60.18 decimals.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct Ema {
+    /// The average, or `None` before the first update.
+    #[cfg_attr(feature = "abi", schemars(with = "Option<crate::U256Str>"))]
+    pub value: Option<U256>,
+    /// Time constant in seconds. Larger values smooth more.
+    pub tau: u64,
+    pub mode: EmaMode,
+}
+
+impl Ema {
+    pub fn new(tau: u64, mode: EmaMode) -> StdResult<Self> {
+        if tau == 0 {
+            return Err(StdError::generic_err("EMA time constant must be positive"));
+        }
+        Ok(Self {
+            value: None,
+            tau,
+            mode,
+        })
+    }
+
+    /// Moves the average toward `value` observed `dt` seconds after the previous update and returns the new average.
+    /// The first update sets the average to `value`.
+    pub fn update(&mut self, value: U256, dt: u64) -> StdResult<U256> {
+        let average = match self.value {
+            None => value,
+            Some(average) => {
+                let alpha = self.alpha(dt)?;
+                if value >= average {
+                    average + muldiv18(value - average, alpha)?
+                } else {
+                    average - muldiv18(average - value, alpha)?
+                }
+            }
+        };
+        self.value = Some(average);
+        Ok(average)
+    }
+
+    /// The smoothing factor for an update `dt` seconds after the previous one, as an unsigned 60.18-decimal number.
+    pub fn alpha(&self, dt: u64) -> StdResult<U256> {
+        let x = muldiv(U256::from(dt), UNIT, U256::from(self.tau))?;
+        if x >= FULL_DECAY {
+            return Ok(UNIT);
+        }
+        match self.mode {
+            EmaMode::Taylor if x <= TAYLOR_MAX_RATIO => {
+                let x2 = muldiv18(x, x)?;
+                let x3 = muldiv18(x2, x)?;
+                Ok(x + x3 / 6 - x2 / 2)
+            }
+            _ => Ok(UNIT - UNIT_SQUARED / ud60x18::exp(x)?),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Interest accrual on cumulative indices and conversions between simple and compounded yearly rates.
+//!
+//! Rates, indices and growth factors are unsigned 60.18-decimal numbers. An index starts at one and is multiplied by
+//! the growth factor of every period, so a balance grows by `index_now / index_then` between two points in time.
+
+use crate::{
+    common::{checked_add, checked_mul, muldiv},
+    ud60x18::{self, UNIT},
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// How [growth_factor] compounds a per-second rate.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub enum Accrual {
+    /// Continuous compounding, `exp(rate * dt)`.
+    Exact,
+    /// The first `terms` powers of the rate in the binomial expansion of `(1 + rate)^dt`, which is cheaper and always
+    /// slightly below the exact value. Aave uses three terms.
+    Binomial { terms: u8 },
+}
+
+/// The factor an index grows by over `dt` seconds at `rate` per second.
+pub fn growth_factor(rate: U256, dt: u64, accrual: Accrual) -> StdResult<U256> {
+    if rate == 0 || dt == 0 {
+        return Ok(UNIT);
+    }
+    match accrual {
+        Accrual::Exact => {
+            let exponent = rate.checked_mul(U256::from(dt)).ok_or_else(|| {
+                StdError::generic_err(format!("Rate {} over {} seconds overflows", rate, dt))
+            })?;
+            ud60x18::exp(exponent)
+        }
+        Accrual::Binomial { terms } => {
+            if terms == 0 {
+                return Err(StdError::generic_err(
+                    "Binomial accrual needs at least one term",
+                ));
+            }
+            // term_k = C(dt, k) * rate^k, built up from term_(k-1).
+            let mut factor = UNIT;
+            let mut term = UNIT;
+            for k in 1..=u64::from(terms).min(dt) {
+                term = muldiv(
+                    muldiv(term, rate, UNIT)?,
+                    U256::from(dt - k + 1),
+                    U256::from(k),
+                )?;
+                factor = checked_add(factor, term)?;
+            }
+            Ok(factor)
+        }
+    }
+}
+
+/// Grows `index` by the interest at `rate` per second over `dt` seconds, rounded down.
+pub fn accrue_index(index: U256, rate: U256, dt: u64, accrual: Accrual) -> StdResult<U256> {
+    muldiv(index, growth_factor(rate, dt, accrual)?, UNIT)
+}
+
+/// Converts a simple yearly rate to the yearly yield when compounding `periods_per_year` times, `(1 + apr / n)^n - 1`.
+pub fn apr_to_apy(apr: U256, periods_per_year: u64) -> StdResult<U256> {
+    let periods = compounding_periods(periods_per_year)?;
+    let compounded = ud60x18::powu(checked_add(UNIT, apr / periods)?, periods)?;
+    Ok(compounded.saturating_sub(UNIT))
+}
+
+/// Converts a simple yearly rate to the yearly yield under continuous compounding, `exp(apr) - 1`.
+pub fn apr_to_apy_continuous(apr: U256) -> StdResult<U256> {
+    Ok(ud60x18::exp(apr)? - UNIT)
+}
+
+/// Converts a yearly yield compounded `periods_per_year` times back to a simple yearly rate,
+/// `n * ((1 + apy)^(1 / n) - 1)`.
+pub fn apy_to_apr(apy: U256, periods_per_year: u64) -> StdResult<U256> {
+    let periods = compounding_periods(periods_per_year)?;
+    let per_period = ud60x18::pow(checked_add(UNIT, apy)?, UNIT / periods)?;
+    checked_mul(per_period.saturating_sub(UNIT), periods)
+}
+
+fn compounding_periods(periods_per_year: u64) -> StdResult<U256> {
+    if periods_per_year == 0 {
+        return Err(StdError::generic_err(
+            "Compounding periods per year must be positive",
+        ));
+    }
+    Ok(U256::from(periods_per_year))
+}
@@ -0,0 +1,212 @@
+//! Account health and liquidation math for a lending market with several collateral and debt assets.
+//!
+//! Amounts are in each asset's utokens and are normalized with [TokenMath] before being valued with [PriceMath], so
+//! values share the normalized precision of the asset type. Ratios (LTV, thresholds, bonuses, close factors and health
+//! factors) are unsigned 60.18-decimal numbers. Every rounding favors the protocol: collateral is valued down, debt is
+//! valued up, liquidators seize rounded down and repay rounded up.
+
+use crate::{
+    common::{checked_add, exp10, muldiv, muldiv_rounded, Rounding},
+    traits::{PriceMath, TokenMath},
+    ud60x18::UNIT,
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// Risk parameters of a collateral asset.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct CollateralParams {
+    /// Fraction of the collateral value that can be borrowed against.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub ltv: U256,
+    /// Fraction of the collateral value the debt may reach before the account can be liquidated.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub liquidation_threshold: U256,
+    /// Extra collateral value a liquidator receives on top of the debt they repay, e.g. `0.05e18` for 5%.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub liquidation_bonus: U256,
+}
+
+impl CollateralParams {
+    pub fn new(ltv: U256, liquidation_threshold: U256, liquidation_bonus: U256) -> StdResult<Self> {
+        if ltv > liquidation_threshold || liquidation_threshold > UNIT {
+            return Err(StdError::generic_err(format!(
+                "LTV {} must be <= liquidation threshold {} <= {}",
+                ltv, liquidation_threshold, UNIT
+            )));
+        }
+        Ok(Self {
+            ltv,
+            liquidation_threshold,
+            liquidation_bonus,
+        })
+    }
+}
+
+/// An amount of collateral held by an account.
+pub struct Collateral<'a, A> {
+    pub asset: &'a A,
+    /// Amount in utokens.
+    pub amount: U256,
+    pub params: CollateralParams,
+}
+
+/// An amount of debt owed by an account.
+pub struct Debt<'a, A> {
+    pub asset: &'a A,
+    /// Amount in utokens.
+    pub amount: U256,
+}
+
+/// Valuation of an account's positions, from [account_health].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AccountHealth {
+    pub collateral_value: U256,
+    /// Sum of collateral values weighted by their LTV.
+    pub borrow_limit: U256,
+    /// Sum of collateral values weighted by their liquidation threshold.
+    pub liquidation_limit: U256,
+    pub debt_value: U256,
+}
+
+impl AccountHealth {
+    /// `liquidation_limit / debt_value`, rounded down. [U256::MAX] without debt.
+    pub fn health_factor(&self) -> StdResult<U256> {
+        if self.debt_value == 0 {
+            return Ok(U256::MAX);
+        }
+        muldiv(self.liquidation_limit, UNIT, self.debt_value)
+    }
+
+    /// `debt_value / collateral_value`, rounded up. [U256::MAX] with debt but no collateral.
+    pub fn ltv(&self) -> StdResult<U256> {
+        if self.debt_value == 0 {
+            return Ok(U256::ZERO);
+        }
+        if self.collateral_value == 0 {
+            return Ok(U256::MAX);
+        }
+        muldiv_rounded(self.debt_value, UNIT, self.collateral_value, Rounding::Up)
+    }
+
+    /// Whether the health factor is below one.
+    pub fn is_liquidatable(&self) -> bool {
+        self.debt_value > self.liquidation_limit
+    }
+
+    /// Value that can still be borrowed before reaching the borrow limit.
+    pub fn available_to_borrow(&self) -> U256 {
+        self.borrow_limit.saturating_sub(self.debt_value)
+    }
+}
+
+/// The outcome of [liquidate], in utokens.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Liquidation {
+    /// Debt repaid by the liquidator.
+    pub repay: U256,
+    /// Collateral given to the liquidator.
+    pub seize: U256,
+}
+
+/// Value of `amount` utokens of `asset`, rounded with `rounding`.
+pub fn asset_value<A: TokenMath + PriceMath>(
+    asset: &A,
+    amount: U256,
+    rounding: Rounding,
+) -> StdResult<U256> {
+    let (normalized, _) = asset.normalize_amount_from_utokens_rounded(amount, rounding)?;
+    muldiv_rounded(
+        normalized,
+        asset.price(),
+        exp10(A::PRICE_PRECISION),
+        rounding,
+    )
+}
+
+/// Utokens of `asset` worth `value`, rounded with `rounding`.
+pub fn asset_amount<A: TokenMath + PriceMath>(
+    asset: &A,
+    value: U256,
+    rounding: Rounding,
+) -> StdResult<U256> {
+    if asset.price() == 0 {
+        return Err(StdError::generic_err("Cannot price an amount at zero"));
+    }
+    let normalized = muldiv_rounded(value, exp10(A::PRICE_PRECISION), asset.price(), rounding)?;
+    let (amount, _) = asset.denormalize_amount_to_utokens_rounded(normalized, rounding)?;
+    Ok(amount)
+}
+
+/// Values the collateral and debt of an account.
+pub fn account_health<A: TokenMath + PriceMath>(
+    collaterals: &[Collateral<A>],
+    debts: &[Debt<A>],
+) -> StdResult<AccountHealth> {
+    let mut health = AccountHealth::default();
+    for collateral in collaterals {
+        let value = asset_value(collateral.asset, collateral.amount, Rounding::Down)?;
+        let params = &collateral.params;
+        health.collateral_value = checked_add(health.collateral_value, value)?;
+        health.borrow_limit = checked_add(health.borrow_limit, muldiv(value, params.ltv, UNIT)?)?;
+        health.liquidation_limit = checked_add(
+            health.liquidation_limit,
+            muldiv(value, params.liquidation_threshold, UNIT)?,
+        )?;
+    }
+    for debt in debts {
+        let value = asset_value(debt.asset, debt.amount, Rounding::Up)?;
+        health.debt_value = checked_add(health.debt_value, value)?;
+    }
+    Ok(health)
+}
+
+/// Most of `debt` utokens a liquidator may repay at once, `debt * close_factor` rounded down.
+pub fn max_repay(debt: U256, close_factor: U256) -> StdResult<U256> {
+    if close_factor > UNIT {
+        return Err(StdError::generic_err(format!(
+            "Close factor {} must be <= {}",
+            close_factor, UNIT
+        )));
+    }
+    muldiv(debt, close_factor, UNIT)
+}
+
+/// Collateral utokens a liquidator seizes for repaying `repay` utokens of debt: the repaid value plus the bonus,
+/// rounded down.
+pub fn seize_amount<A: TokenMath + PriceMath>(
+    repay: U256,
+    debt_asset: &A,
+    collateral_asset: &A,
+    liquidation_bonus: U256,
+) -> StdResult<U256> {
+    let repaid = asset_value(debt_asset, repay, Rounding::Down)?;
+    let with_bonus = muldiv(repaid, checked_add(UNIT, liquidation_bonus)?, UNIT)?;
+    asset_amount(collateral_asset, with_bonus, Rounding::Down)
+}
+
+/// Liquidates up to `repay` utokens of `debt`, capped by the close factor. When the collateral cannot cover the
+/// repayment plus bonus, all of it is seized and the repayment shrinks to what it covers, rounded up.
+pub fn liquidate<A: TokenMath + PriceMath>(
+    repay: U256,
+    debt: &Debt<A>,
+    collateral: &Collateral<A>,
+    close_factor: U256,
+) -> StdResult<Liquidation> {
+    let repay = repay.min(max_repay(debt.amount, close_factor)?);
+    let bonus = collateral.params.liquidation_bonus;
+    let seize = seize_amount(repay, debt.asset, collateral.asset, bonus)?;
+    if seize <= collateral.amount {
+        return Ok(Liquidation { repay, seize });
+    }
+
+    let seized = asset_value(collateral.asset, collateral.amount, Rounding::Down)?;
+    let covered = muldiv_rounded(seized, UNIT, checked_add(UNIT, bonus)?, Rounding::Up)?;
+    Ok(Liquidation {
+        repay: asset_amount(debt.asset, covered, Rounding::Up)?.min(repay),
+        seize: collateral.amount,
+    })
+}
@@ -0,0 +1,6 @@
+//! Money market math: interest rate curves, interest accrual, scaled balances and liquidations.
+
+pub mod index;
+pub mod liquidation;
+pub mod rate_model;
+pub mod scaled;
@@ -0,0 +1,198 @@
+//! Interest rate models that map the utilization of a lending pool to borrow and supply rates per second.
+//!
+//! Utilization, rates and reserve factors are unsigned 60.18-decimal numbers, e.g. a utilization of 80% is `0.8e18`.
+//! Rates are per second; use [per_second] to convert yearly rates when configuring a model.
+
+use crate::{
+    common::{checked_add, muldiv, SECONDS_PER_YEAR},
+    ud60x18::UNIT,
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// A curve from utilization to borrow rate. Implement it to plug a custom curve into a protocol.
+pub trait InterestRateModel {
+    /// The borrow rate per second at `utilization`.
+    fn borrow_rate(&self, utilization: U256) -> StdResult<U256>;
+
+    /// The rate per second earned by suppliers at `utilization`, after `reserve_factor` of the interest goes to the
+    /// protocol: `borrow_rate * utilization * (1 - reserve_factor)`, rounded down.
+    fn supply_rate(&self, utilization: U256, reserve_factor: U256) -> StdResult<U256> {
+        if reserve_factor > UNIT {
+            return Err(StdError::generic_err(format!(
+                "Reserve factor {} must be <= {}",
+                reserve_factor, UNIT
+            )));
+        }
+        let borrow_rate = self.borrow_rate(utilization)?;
+        let to_suppliers = muldiv(borrow_rate, UNIT - reserve_factor, UNIT)?;
+        muldiv(to_suppliers, utilization, UNIT)
+    }
+}
+
+/// Fraction of the supplied assets that are borrowed, `borrows / (cash + borrows - reserves)`, rounded down. Zero when
+/// nothing is borrowed.
+pub fn utilization(cash: U256, borrows: U256, reserves: U256) -> StdResult<U256> {
+    if borrows == 0 {
+        return Ok(U256::ZERO);
+    }
+    let supplied = checked_add(cash, borrows)?
+        .checked_sub(reserves)
+        .ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Reserves {} exceed cash {} plus borrows {}",
+                reserves, cash, borrows
+            ))
+        })?;
+    if supplied == 0 {
+        return Err(StdError::generic_err("Utilization of an empty pool"));
+    }
+    muldiv(borrows, UNIT, supplied)
+}
+
+/// Converts a yearly rate to a rate per second, rounded down.
+pub fn per_second(yearly_rate: U256) -> U256 {
+    yearly_rate / U256::from(SECONDS_PER_YEAR)
+}
+
+/// `base_rate + utilization * multiplier`.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct LinearRateModel {
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub base_rate: U256,
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub multiplier: U256,
+}
+
+impl LinearRateModel {
+    pub fn new(base_rate: U256, multiplier: U256) -> Self {
+        Self {
+            base_rate,
+            multiplier,
+        }
+    }
+}
+
+impl InterestRateModel for LinearRateModel {
+    fn borrow_rate(&self, utilization: U256) -> StdResult<U256> {
+        checked_add(self.base_rate, muldiv(utilization, self.multiplier, UNIT)?)
+    }
+}
+
+/// Compound's jump rate model: linear up to `kink`, then rising by `jump_multiplier` per unit of utilization above it.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct JumpRateModel {
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub base_rate: U256,
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub multiplier: U256,
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub jump_multiplier: U256,
+    /// Utilization above which `jump_multiplier` applies. Must be at most one.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub kink: U256,
+}
+
+impl JumpRateModel {
+    pub fn new(
+        base_rate: U256,
+        multiplier: U256,
+        jump_multiplier: U256,
+        kink: U256,
+    ) -> StdResult<Self> {
+        if kink > UNIT {
+            return Err(StdError::generic_err(format!(
+                "Kink {} must be <= {}",
+                kink, UNIT
+            )));
+        }
+        Ok(Self {
+            base_rate,
+            multiplier,
+            jump_multiplier,
+            kink,
+        })
+    }
+}
+
+impl InterestRateModel for JumpRateModel {
+    fn borrow_rate(&self, utilization: U256) -> StdResult<U256> {
+        let normal = LinearRateModel::new(self.base_rate, self.multiplier);
+        if utilization <= self.kink {
+            return normal.borrow_rate(utilization);
+        }
+        let excess = muldiv(utilization - self.kink, self.jump_multiplier, UNIT)?;
+        checked_add(normal.borrow_rate(self.kink)?, excess)
+    }
+}
+
+/// Aave's two-slope model: rises from `base_rate` by `slope1` up to `optimal_utilization`, then by `slope2` more up to
+/// full utilization.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct SlopeRateModel {
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub base_rate: U256,
+    /// Rate added from zero to optimal utilization.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub slope1: U256,
+    /// Rate added from optimal to full utilization.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub slope2: U256,
+    /// Must be strictly between zero and one.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub optimal_utilization: U256,
+}
+
+impl SlopeRateModel {
+    pub fn new(
+        base_rate: U256,
+        slope1: U256,
+        slope2: U256,
+        optimal_utilization: U256,
+    ) -> StdResult<Self> {
+        check_optimal_utilization(optimal_utilization)?;
+        Ok(Self {
+            base_rate,
+            slope1,
+            slope2,
+            optimal_utilization,
+        })
+    }
+}
+
+impl InterestRateModel for SlopeRateModel {
+    fn borrow_rate(&self, utilization: U256) -> StdResult<U256> {
+        // The field is public and may come from storage, so it is checked here as well as in `new`.
+        check_optimal_utilization(self.optimal_utilization)?;
+        if utilization <= self.optimal_utilization {
+            let ratio = muldiv(utilization, UNIT, self.optimal_utilization)?;
+            return checked_add(self.base_rate, muldiv(self.slope1, ratio, UNIT)?);
+        }
+        let excess = muldiv(
+            utilization - self.optimal_utilization,
+            UNIT,
+            UNIT - self.optimal_utilization,
+        )?;
+        checked_add(
+            checked_add(self.base_rate, self.slope1)?,
+            muldiv(self.slope2, excess, UNIT)?,
+        )
+    }
+}
+
+fn check_optimal_utilization(optimal_utilization: U256) -> StdResult<()> {
+    if optimal_utilization == 0 || optimal_utilization >= UNIT {
+        return Err(StdError::generic_err(format!(
+            "Optimal utilization {} must be between 0 and {} exclusive",
+            optimal_utilization, UNIT
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Scaled balances, as used by Aave's aTokens and debt tokens: balances are stored divided by a liquidity index and
+//! read back multiplied by the current index, so every balance grows with the index without being touched.
+//!
+//! Indices are ray numbers (27 decimals). Amounts are in the token's own units.
+
+use crate::{
+    common::{exp10, muldiv_rounded, Rounding},
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// One with 18 decimals.
+pub const WAD: U256 = exp10(18);
+/// One with 27 decimals.
+pub const RAY: U256 = exp10(27);
+pub const WAD_RAY_RATIO: U256 = exp10(9);
+
+/// `a * b` for ray numbers.
+pub fn ray_mul(a: U256, b: U256, rounding: Rounding) -> StdResult<U256> {
+    muldiv_rounded(a, b, RAY, rounding)
+}
+
+/// `a / b` for ray numbers.
+pub fn ray_div(a: U256, b: U256, rounding: Rounding) -> StdResult<U256> {
+    muldiv_rounded(a, RAY, b, rounding)
+}
+
+/// Converts a wad number, e.g. an unsigned 60.18-decimal index from [super::index], to a ray number.
+pub fn wad_to_ray(a: U256) -> StdResult<U256> {
+    a.checked_mul(WAD_RAY_RATIO)
+        .ok_or_else(|| StdError::generic_err(format!("Wad {} does not fit in a ray", a)))
+}
+
+/// Converts a ray number to a wad number, rounded with `rounding`.
+pub fn ray_to_wad(a: U256, rounding: Rounding) -> StdResult<U256> {
+    muldiv_rounded(a, U256::ONE, WAD_RAY_RATIO, rounding)
+}
+
+/// Which way a scaled balance rounds. Every operation rounds in the protocol's favor.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub enum ScaledKind {
+    /// A deposit, like an aToken: minting and reading round down, burning rounds up.
+    Supply,
+    /// A debt, like a variable debt token: minting and reading round up, burning rounds down.
+    Debt,
+}
+
+impl ScaledKind {
+    /// Rounding when scaling balances up, which the protocol wants low for deposits and high for debts.
+    fn owed(self) -> Rounding {
+        match self {
+            ScaledKind::Supply => Rounding::Down,
+            ScaledKind::Debt => Rounding::Up,
+        }
+    }
+
+    /// Rounding when an amount leaves the balance, the opposite of [ScaledKind::owed].
+    fn repaid(self) -> Rounding {
+        match self {
+            ScaledKind::Supply => Rounding::Up,
+            ScaledKind::Debt => Rounding::Down,
+        }
+    }
+}
+
+/// A balance stored divided by the index at which it changed. Use one per account and one for the total.
+#[borsh_serde]
+#[derive(Copy, Eq, Default)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct ScaledBalance {
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub scaled: U256,
+}
+
+impl ScaledBalance {
+    pub fn new(scaled: U256) -> Self {
+        Self { scaled }
+    }
+
+    /// The balance at `index`.
+    pub fn balance(&self, index: U256, kind: ScaledKind) -> StdResult<U256> {
+        ray_mul(self.scaled, index, kind.owed())
+    }
+
+    /// Adds `amount` at `index` and returns the scaled amount added.
+    pub fn mint(&mut self, amount: U256, index: U256, kind: ScaledKind) -> StdResult<U256> {
+        let scaled = scale(amount, index, kind.owed())?;
+        self.scaled = self.scaled.checked_add(scaled).ok_or_else(|| {
+            StdError::generic_err(format!(
+                "Minting {} scaled onto {} overflows",
+                scaled, self.scaled
+            ))
+        })?;
+        Ok(scaled)
+    }
+
+    /// Removes `amount` at `index` and returns the scaled amount removed. Removing the whole balance always clears it,
+    /// so no dust is left behind by rounding.
+    pub fn burn(&mut self, amount: U256, index: U256, kind: ScaledKind) -> StdResult<U256> {
+        let balance = self.balance(index, kind)?;
+        if amount > balance {
+            return Err(StdError::generic_err(format!(
+                "Cannot burn {} from a balance of {}",
+                amount, balance
+            )));
+        }
+        let scaled = if amount == balance {
+            self.scaled
+        } else {
+            scale(amount, index, kind.repaid())?.min(self.scaled)
+        };
+        self.scaled -= scaled;
+        Ok(scaled)
+    }
+}
+
+/// `amount / index`, erroring on a zero index.
+fn scale(amount: U256, index: U256, rounding: Rounding) -> StdResult<U256> {
+    if index == 0 {
+        return Err(StdError::generic_err("Liquidity index must be positive"));
+    }
+    ray_div(amount, index, rounding)
+}
@@ -1,6 +1,12 @@
 //! Math library for advanced fixed-point math that works with numbers which are considered to have 18 trailing decimals.
 //! Uses U256 and I256 for better performance.
 
+// Lets code generated by btr_macros refer to this crate by name from within it.
+extern crate self as better_secret_math;
+
+#[cfg(feature = "abi")]
+pub mod abi;
+pub mod aggregate;
 pub mod macros;
 pub use ethnum::*;
 pub use primitives::*;
@@ -8,11 +14,19 @@ pub mod asserter;
 mod primitives;
 
 pub mod common;
+pub mod convert;
+pub mod ema;
+pub mod lending;
+pub mod oracle;
+pub mod rewards;
+pub mod ring_buffer;
 pub mod traits;
+pub mod twap;
 pub mod sd59x18;
 pub mod ud60x18;
 
 mod asm;
+mod decimal;
 pub(crate) mod tens;
 
 #[cfg(test)]
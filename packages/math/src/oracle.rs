@@ -0,0 +1,114 @@
+//! Oracle prices that carry their own denominations, precision and publish time, so prices from feeds with different
+//! decimals can be inverted, crossed and rescaled without losing track of what they quote.
+
+use crate::{
+    common::{checked_mul, exp10, muldiv, muldiv_rounded, Rounding},
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// The price of one `base` in `quote`, e.g. `ATOM/USD`.
+#[borsh_serde]
+#[derive(Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct OraclePrice {
+    /// Denomination being priced.
+    pub base: String,
+    /// Denomination the price is quoted in.
+    pub quote: String,
+    /// Price scaled by 10^`precision`.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub price: U256,
+    /// Number of decimals in `price`. Must be at most 77.
+    pub precision: u8,
+    /// Time the price was published in seconds.
+    pub publish_time: u64,
+}
+
+impl OraclePrice {
+    pub fn new(
+        base: impl Into<String>,
+        quote: impl Into<String>,
+        price: impl Into<U256>,
+        precision: u8,
+        publish_time: u64,
+    ) -> Self {
+        Self {
+            base: base.into(),
+            quote: quote.into(),
+            price: price.into(),
+            precision,
+            publish_time,
+        }
+    }
+
+    /// Gets the price of one `quote` in `base` with the same precision, computed as 10^(2 * precision) / price with
+    /// full precision and rounded down.
+    pub fn invert(&self) -> StdResult<OraclePrice> {
+        if self.price == 0 {
+            return Err(StdError::generic_err(format!(
+                "Cannot invert zero price of {}/{}",
+                self.base, self.quote
+            )));
+        }
+        let scale = precision_scale(self.precision)?;
+        Ok(OraclePrice {
+            base: self.quote.clone(),
+            quote: self.base.clone(),
+            price: muldiv(scale, scale, self.price)?,
+            precision: self.precision,
+            publish_time: self.publish_time,
+        })
+    }
+
+    /// Derives the price of `self.base` in `other.base` from two prices with the same quote, e.g. `A/USD ÷ B/USD =
+    /// A/B`, rounded down. The result has the precision of `self` and the older of the two publish times.
+    pub fn cross(&self, other: &OraclePrice) -> StdResult<OraclePrice> {
+        if self.quote != other.quote {
+            return Err(StdError::generic_err(format!(
+                "Cannot cross {}/{} with {}/{}",
+                self.base, self.quote, other.base, other.quote
+            )));
+        }
+        if other.price == 0 {
+            return Err(StdError::generic_err(format!(
+                "Cannot cross with zero price of {}/{}",
+                other.base, other.quote
+            )));
+        }
+        Ok(OraclePrice {
+            base: self.base.clone(),
+            quote: other.base.clone(),
+            price: muldiv(self.price, precision_scale(other.precision)?, other.price)?,
+            precision: self.precision,
+            publish_time: self.publish_time.min(other.publish_time),
+        })
+    }
+
+    /// Gets the same price with `precision` decimals, rounding with `rounding` when decimals are dropped.
+    pub fn rescale(&self, precision: u8, rounding: Rounding) -> StdResult<OraclePrice> {
+        let price = if precision >= self.precision {
+            checked_mul(self.price, precision_scale(precision - self.precision)?)?
+        } else {
+            let divisor = precision_scale(self.precision - precision)?;
+            muldiv_rounded(self.price, U256::ONE, divisor, rounding)?
+        };
+        Ok(OraclePrice {
+            price,
+            precision,
+            ..self.clone()
+        })
+    }
+}
+
+/// Gets 10^`precision`, erroring instead of panicking when it does not fit in a U256.
+fn precision_scale(precision: u8) -> StdResult<U256> {
+    if precision > 77 {
+        return Err(StdError::generic_err(format!(
+            "Price precision {} must be <= 77",
+            precision
+        )));
+    }
+    Ok(exp10(precision))
+}
@@ -1,13 +1,17 @@
 //! Helper classes to serialize and deserialize large integer types into base-10 string
 //! representations.
 //! NOTE: JSON standard can only work with integer up to 53 bits. So we need helper classes for
-//! 64-bit and 128-bit integers.
+//! 64-bit, 128-bit and 256-bit integers.
 
 use borsh::{BorshDeserialize, BorshSerialize};
+use ethnum::{I256, U256};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 macro_rules! impl_str_type {
     ($iden: ident, $ty: tt) => {
+        impl_str_type!($iden, $ty, str::parse::<$ty>);
+    };
+    ($iden: ident, $ty: tt, $parse: expr) => {
         #[derive(
             Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, BorshDeserialize, BorshSerialize,
         )]
@@ -43,7 +47,7 @@ macro_rules! impl_str_type {
                 D: Deserializer<'de>,
             {
                 let s: String = Deserialize::deserialize(deserializer)?;
-                Ok(Self(str::parse::<$ty>(&s).map_err(|err| {
+                Ok(Self($parse(&s).map_err(|err| {
                     serde::de::Error::custom(err.to_string())
                 })?))
             }
@@ -70,6 +74,36 @@ impl_str_type!(U128, u128);
 impl_str_type!(U64, u64);
 impl_str_type!(I128, i128);
 impl_str_type!(I64, i64);
+// 256-bit values are written in base 10, but `0x` prefixed hex is accepted when deserializing.
+impl_str_type!(U256Str, U256, U256::from_str_prefixed);
+impl_str_type!(I256Str, I256, I256::from_str_prefixed);
+
+/// Serde `with` modules for serializing plain 256-bit fields the same way as the string wrappers above.
+macro_rules! impl_str_with {
+    ($module: ident, $iden: ident, $ty: tt) => {
+        #[doc = concat!("Use with `#[serde(with = \"better_secret_math::", stringify!($module), "\")]` to (de)serialize a [", stringify!($ty), "] field like [", stringify!($iden), "].")]
+        pub mod $module {
+            use super::{Deserialize, Deserializer, Serialize, Serializer, $iden, $ty};
+
+            pub fn serialize<S>(v: &$ty, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                Serialize::serialize(&$iden(*v), serializer)
+            }
+
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<$ty, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                <$iden as Deserialize>::deserialize(deserializer).map(|v| v.0)
+            }
+        }
+    };
+}
+
+impl_str_with!(u256_str, U256Str, U256);
+impl_str_with!(i256_str, I256Str, I256);
 
 #[cfg(test)]
 mod tests {
@@ -113,6 +147,58 @@ mod tests {
         assert!(I128::from(i128::min_value()) < I128::from(i128::max_value()));
     }
 
+    #[test]
+    fn test_u256() {
+        test_serde!(U256Str, U256, U256::ZERO);
+        test_serde!(U256Str, U256, U256::ONE);
+        test_serde!(U256Str, U256, U256::from(u128::MAX) * 3);
+        test_serde!(U256Str, U256, U256::MAX);
+        assert_eq!(
+            serde_json::to_string(&U256Str(U256::new(42))).unwrap(),
+            "\"42\""
+        );
+        let hex: U256Str = serde_json::from_str("\"0x2a\"").unwrap();
+        assert_eq!(hex.0, U256::new(42));
+        assert!(serde_json::from_str::<U256Str>("\"-1\"").is_err());
+        assert!(serde_json::from_str::<U256Str>("42").is_err());
+    }
+
+    #[test]
+    fn test_i256() {
+        test_serde!(I256Str, I256, I256::ZERO);
+        test_serde!(I256Str, I256, I256::MINUS_ONE);
+        test_serde!(I256Str, I256, I256::MAX);
+        test_serde!(I256Str, I256, I256::MIN);
+        assert_eq!(
+            serde_json::to_string(&I256Str(I256::new(-42))).unwrap(),
+            "\"-42\""
+        );
+        let hex: I256Str = serde_json::from_str("\"-0x2a\"").unwrap();
+        assert_eq!(hex.0, I256::new(-42));
+    }
+
+    #[test]
+    fn test_with_modules() {
+        #[derive(Serialize, Deserialize, PartialEq, Debug)]
+        struct Amounts {
+            #[serde(with = "u256_str")]
+            a: U256,
+            #[serde(with = "i256_str")]
+            b: I256,
+        }
+
+        let amounts = Amounts {
+            a: U256::MAX,
+            b: I256::new(-7),
+        };
+        let json = serde_json::to_string(&amounts).unwrap();
+        assert_eq!(
+            json,
+            "{\"a\":\"115792089237316195423570985008687907853269984665640564039457584007913129639935\",\"b\":\"-7\"}"
+        );
+        assert_eq!(serde_json::from_str::<Amounts>(&json).unwrap(), amounts);
+    }
+
     #[test]
     fn test_u64() {
         test_serde!(U64, u64, 0);
@@ -0,0 +1,263 @@
+//! Staking rewards with the MasterChef/Synthetix `reward_per_token` accumulator.
+//!
+//! A global [RewardAccumulator] sums the rewards earned per staked token since the start, scaled by [UNIT]. Each staker
+//! keeps a [RewardCheckpoint] of the accumulator at their last balance change, so their rewards since then are
+//! `balance * (reward_per_token - reward_per_token_paid)`. Checkpoint a staker before changing their balance and update
+//! the accumulator before changing the total staked, or rewards are credited at the wrong rate.
+
+use crate::{
+    common::{checked_add, checked_mul, muldiv},
+    ud60x18::UNIT,
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+use std::collections::BTreeMap;
+
+/// Rewards per staked token of one reward token, streamed at `rate` per second until `period_finish` and/or
+/// distributed in lumps.
+#[borsh_serde]
+#[derive(Copy, Eq, Default)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct RewardAccumulator {
+    /// Rewards per staked token since the start, scaled by [UNIT].
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub reward_per_token: U256,
+    /// Rewards streamed per second.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub rate: U256,
+    /// Time `reward_per_token` was last brought up to date, in seconds.
+    pub last_update: u64,
+    /// Time the stream ends, in seconds.
+    pub period_finish: u64,
+}
+
+impl RewardAccumulator {
+    pub fn new(now: u64) -> Self {
+        Self {
+            last_update: now,
+            period_finish: now,
+            ..Self::default()
+        }
+    }
+
+    /// `reward_per_token` at `now` when `total_staked` tokens have been staked since the last update, rounded down.
+    /// Rewards streamed while nothing is staked are not distributed.
+    pub fn reward_per_token_at(&self, now: u64, total_staked: U256) -> StdResult<U256> {
+        let elapsed = self.last_applicable(now).saturating_sub(self.last_update);
+        if total_staked == 0 || elapsed == 0 {
+            return Ok(self.reward_per_token);
+        }
+        let streamed = checked_mul(self.rate, U256::from(elapsed))?;
+        checked_add(self.reward_per_token, muldiv(streamed, UNIT, total_staked)?)
+    }
+
+    /// Brings `reward_per_token` up to `now` and returns it.
+    pub fn update(&mut self, now: u64, total_staked: U256) -> StdResult<U256> {
+        self.reward_per_token = self.reward_per_token_at(now, total_staked)?;
+        self.last_update = self.last_applicable(now).max(self.last_update);
+        Ok(self.reward_per_token)
+    }
+
+    /// Streams `amount` over the next `duration` seconds, together with whatever was left of the current stream.
+    pub fn notify_reward(
+        &mut self,
+        amount: U256,
+        duration: u64,
+        now: u64,
+        total_staked: U256,
+    ) -> StdResult<()> {
+        if duration == 0 {
+            return Err(StdError::generic_err("Reward duration must be positive"));
+        }
+        self.update(now, total_staked)?;
+        let leftover = checked_mul(
+            self.rate,
+            U256::from(self.period_finish.saturating_sub(now)),
+        )?;
+        self.rate = checked_add(amount, leftover)? / U256::from(duration);
+        self.last_update = now;
+        self.period_finish = now.checked_add(duration).ok_or_else(|| {
+            StdError::generic_err(format!("Reward period from {} overflows", now))
+        })?;
+        Ok(())
+    }
+
+    /// Distributes `amount` to the `total_staked` tokens at once, as MasterChef does on harvest.
+    pub fn distribute(&mut self, amount: U256, total_staked: U256) -> StdResult<U256> {
+        if total_staked == 0 {
+            return Err(StdError::generic_err(
+                "Cannot distribute rewards when nothing is staked",
+            ));
+        }
+        self.reward_per_token =
+            checked_add(self.reward_per_token, muldiv(amount, UNIT, total_staked)?)?;
+        Ok(self.reward_per_token)
+    }
+
+    fn last_applicable(&self, now: u64) -> u64 {
+        now.min(self.period_finish)
+    }
+}
+
+/// A staker's position against a [RewardAccumulator].
+#[borsh_serde]
+#[derive(Copy, Eq, Default)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct RewardCheckpoint {
+    /// `reward_per_token` when the staker was last checkpointed.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub reward_per_token_paid: U256,
+    /// Rewards earned up to the last checkpoint and not yet claimed.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub rewards: U256,
+}
+
+impl RewardCheckpoint {
+    /// Rewards earned by `balance` staked since the last checkpoint up to `reward_per_token`, rounded down.
+    pub fn earned(&self, balance: U256, reward_per_token: U256) -> StdResult<U256> {
+        let delta = reward_per_token
+            .checked_sub(self.reward_per_token_paid)
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "Reward per token {} is below the checkpoint at {}",
+                    reward_per_token, self.reward_per_token_paid
+                ))
+            })?;
+        checked_add(self.rewards, muldiv(balance, delta, UNIT)?)
+    }
+
+    /// Credits the rewards earned by `balance` up to `reward_per_token`. Call it before changing the balance.
+    pub fn checkpoint(&mut self, balance: U256, reward_per_token: U256) -> StdResult<U256> {
+        self.rewards = self.earned(balance, reward_per_token)?;
+        self.reward_per_token_paid = reward_per_token;
+        Ok(self.rewards)
+    }
+
+    /// Rewards `balance` could claim at `now`, without updating anything.
+    pub fn pending_rewards(
+        &self,
+        accumulator: &RewardAccumulator,
+        balance: U256,
+        now: u64,
+        total_staked: U256,
+    ) -> StdResult<U256> {
+        self.earned(balance, accumulator.reward_per_token_at(now, total_staked)?)
+    }
+
+    /// Takes the credited rewards.
+    pub fn claim(&mut self) -> U256 {
+        std::mem::take(&mut self.rewards)
+    }
+}
+
+/// A [RewardAccumulator] per reward token.
+#[borsh_serde]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct MultiRewardAccumulator<K: Ord> {
+    pub tokens: BTreeMap<K, RewardAccumulator>,
+}
+
+impl<K: Ord> Default for MultiRewardAccumulator<K> {
+    fn default() -> Self {
+        Self {
+            tokens: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K: Ord + Clone> MultiRewardAccumulator<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, token: &K) -> Option<&RewardAccumulator> {
+        self.tokens.get(token)
+    }
+
+    /// Gets the accumulator for `token`, adding one starting at `now` if it does not exist.
+    pub fn token_mut(&mut self, token: K, now: u64) -> &mut RewardAccumulator {
+        self.tokens
+            .entry(token)
+            .or_insert_with(|| RewardAccumulator::new(now))
+    }
+
+    /// Brings every accumulator up to `now`. On error no accumulator is updated.
+    pub fn update(&mut self, now: u64, total_staked: U256) -> StdResult<()> {
+        let mut tokens = self.tokens.clone();
+        for accumulator in tokens.values_mut() {
+            accumulator.update(now, total_staked)?;
+        }
+        self.tokens = tokens;
+        Ok(())
+    }
+}
+
+/// A staker's [RewardCheckpoint] per reward token.
+#[borsh_serde]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct UserRewards<K: Ord> {
+    pub checkpoints: BTreeMap<K, RewardCheckpoint>,
+}
+
+impl<K: Ord> Default for UserRewards<K> {
+    fn default() -> Self {
+        Self {
+            checkpoints: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K: Ord + Clone> UserRewards<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Credits the rewards earned by `balance` for every token of `accumulators`, which must be up to date. Call it
+    /// before changing the balance. A token the staker has not seen before credits everything since it was added. On
+    /// error no checkpoint is updated.
+    pub fn checkpoint(
+        &mut self,
+        accumulators: &MultiRewardAccumulator<K>,
+        balance: U256,
+    ) -> StdResult<()> {
+        let mut checkpoints = self.checkpoints.clone();
+        for (token, accumulator) in &accumulators.tokens {
+            checkpoints
+                .entry(token.clone())
+                .or_default()
+                .checkpoint(balance, accumulator.reward_per_token)?;
+        }
+        self.checkpoints = checkpoints;
+        Ok(())
+    }
+
+    /// Rewards per token `balance` could claim at `now`, without updating anything.
+    pub fn pending_rewards(
+        &self,
+        accumulators: &MultiRewardAccumulator<K>,
+        balance: U256,
+        now: u64,
+        total_staked: U256,
+    ) -> StdResult<BTreeMap<K, U256>> {
+        accumulators
+            .tokens
+            .iter()
+            .map(|(token, accumulator)| {
+                let checkpoint = self.checkpoints.get(token).copied().unwrap_or_default();
+                let pending =
+                    checkpoint.pending_rewards(accumulator, balance, now, total_staked)?;
+                Ok((token.clone(), pending))
+            })
+            .collect()
+    }
+
+    /// Takes the credited rewards of every token, leaving out tokens with nothing to claim.
+    pub fn claim(&mut self) -> BTreeMap<K, U256> {
+        self.checkpoints
+            .iter_mut()
+            .map(|(token, checkpoint)| (token.clone(), checkpoint.claim()))
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+}
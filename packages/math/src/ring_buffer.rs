@@ -0,0 +1,135 @@
+//! A fixed-size ring buffer of the most recent items, for histories kept in contract storage such as
+//! [crate::traits::RebaseHistory] and [crate::twap::TwapOracle].
+
+use borsh::{
+    maybestd::io::{Error, ErrorKind, Read, Result as IoResult},
+    BorshDeserialize, BorshSerialize,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+use serde::{Deserialize, Serialize};
+
+/// At most `capacity` items, oldest first. Pushing past the capacity overwrites the oldest item.
+///
+/// The fields are private and checked when deserializing, so a buffer read from storage can never index out of bounds.
+#[derive(Serialize, Deserialize, BorshSerialize, Clone, Debug, PartialEq, Eq)]
+#[serde(try_from = "RawRingBuffer<T>")]
+#[cfg_attr(
+    feature = "abi",
+    derive(schemars::JsonSchema),
+    schemars(deny_unknown_fields)
+)]
+pub struct RingBuffer<T> {
+    capacity: u32,
+    items: Vec<T>,
+    /// Index of the oldest item in `items`. Zero until the buffer is full.
+    head: u32,
+}
+
+/// The serialized fields of a [RingBuffer], before they are checked.
+#[borsh_serde]
+struct RawRingBuffer<T> {
+    capacity: u32,
+    items: Vec<T>,
+    head: u32,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: u32) -> StdResult<Self> {
+        Self::try_from(RawRingBuffer {
+            capacity,
+            items: Vec::new(),
+            head: 0,
+        })
+    }
+
+    /// Maximum number of items kept.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Iterates over the items from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        let (newer, older) = self.items.split_at(self.head as usize);
+        older.iter().chain(newer.iter())
+    }
+
+    pub fn oldest(&self) -> Option<&T> {
+        self.iter().next()
+    }
+
+    pub fn latest(&self) -> Option<&T> {
+        self.iter().next_back()
+    }
+
+    pub fn latest_mut(&mut self) -> Option<&mut T> {
+        let index = match self.head {
+            0 => self.items.len().checked_sub(1)?,
+            head => head as usize - 1,
+        };
+        self.items.get_mut(index)
+    }
+
+    /// Adds `item` as the latest item, overwriting the oldest one when the buffer is full.
+    pub fn push(&mut self, item: T) {
+        if self.items.len() < self.capacity as usize {
+            self.items.push(item);
+        } else {
+            self.items[self.head as usize] = item;
+            self.head = (self.head + 1) % self.capacity;
+        }
+    }
+}
+
+impl<T> TryFrom<RawRingBuffer<T>> for RingBuffer<T> {
+    type Error = StdError;
+
+    fn try_from(raw: RawRingBuffer<T>) -> StdResult<Self> {
+        let RawRingBuffer {
+            capacity,
+            items,
+            head,
+        } = raw;
+        if capacity == 0 {
+            return Err(StdError::generic_err(
+                "Ring buffer capacity must be positive",
+            ));
+        }
+        if items.len() > capacity as usize {
+            return Err(StdError::generic_err(format!(
+                "Ring buffer of capacity {} cannot hold {} items",
+                capacity,
+                items.len()
+            )));
+        }
+        let full = items.len() == capacity as usize;
+        if head >= capacity || (head != 0 && !full) {
+            return Err(StdError::generic_err(format!(
+                "Ring buffer head {} is out of range for {} of {} items",
+                head,
+                items.len(),
+                capacity
+            )));
+        }
+        Ok(Self {
+            capacity,
+            items,
+            head,
+        })
+    }
+}
+
+impl<T: BorshDeserialize> BorshDeserialize for RingBuffer<T> {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> IoResult<Self> {
+        Self::try_from(RawRingBuffer::deserialize_reader(reader)?)
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err.to_string()))
+    }
+}
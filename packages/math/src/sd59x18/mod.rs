@@ -1,5 +1,6 @@
 use crate::asm::Asm;
-use crate::common::{msb, muldiv18};
+use crate::common::{msb, muldiv18, Rounding};
+use crate::decimal;
 use cosmwasm_std::{StdError, StdResult};
 use ethnum::{I256, U256};
 
@@ -251,6 +252,44 @@ pub fn sqrt(x: I256) -> StdResult<I256> {
     Ok(common::sqrt((x * UNIT).as_u256()).as_i256())
 }
 
+/// Parses a base-10 string such as `"-1.25"` or `"3e-4"` into a signed 59.18-decimal fixed-point number.
+///
+/// Requirements:
+/// - The string must not have more than 18 decimals; see [parse_decimal_rounded] to round them away instead.
+/// - The value must fit between MIN_SD59X18 and MAX_SD59X18.
+pub fn parse_decimal(s: &str) -> StdResult<I256> {
+    parse_with(s, None)
+}
+
+/// Same as [parse_decimal], but rounds digits beyond the 18th decimal using `rounding`. Rounding is applied to the
+/// magnitude, so `Rounding::Down` truncates toward zero.
+pub fn parse_decimal_rounded(s: &str, rounding: Rounding) -> StdResult<I256> {
+    parse_with(s, Some(rounding))
+}
+
+fn parse_with(s: &str, rounding: Option<Rounding>) -> StdResult<I256> {
+    let (negative, x) = decimal::parse(s, rounding).map_err(Into::<StdError>::into)?;
+    let min = MIN_SD59X18.unsigned_abs();
+    if x > min || (!negative && x == min) {
+        return Err(decimal::DecimalError::Overflow(s.to_string()).into());
+    }
+    // Wrapping negation maps 2^255 onto MIN_SD59X18.
+    let x = x.as_i256();
+    Ok(if negative { x.wrapping_neg() } else { x })
+}
+
+/// Formats a signed 59.18-decimal fixed-point number as a base-10 string with at most `max_decimals` decimals.
+///
+/// Excess decimals are truncated toward zero and trailing zeros are trimmed.
+pub fn format_decimal(x: I256, max_decimals: u8) -> String {
+    let formatted = decimal::format(x.unsigned_abs(), max_decimals);
+    if x < 0 && formatted != "0" {
+        format!("-{}", formatted)
+    } else {
+        formatted
+    }
+}
+
 /// Gets the scale as a signed int 256
 pub fn scale() -> I256 {
     UNIT
@@ -274,4 +313,54 @@ mod test {
         assert_eq!(MIN_WHOLE_SD59X18, int2);
         assert_eq!(MAX_WHOLE_SD59X18, int);
     }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(
+            parse_decimal("-1.25").unwrap(),
+            I256::new(-1_250_000_000_000_000_000)
+        );
+        assert_eq!(
+            parse_decimal("2.5e-1").unwrap(),
+            I256::new(250_000_000_000_000_000)
+        );
+        assert_eq!(
+            parse_decimal(
+                "-57896044618658097711785492504343953926634992332820282019728.792003956564819968"
+            )
+            .unwrap(),
+            MIN_SD59X18
+        );
+        assert_eq!(
+            parse_decimal(
+                "57896044618658097711785492504343953926634992332820282019728.792003956564819967"
+            )
+            .unwrap(),
+            MAX_SD59X18
+        );
+        assert!(parse_decimal(
+            "57896044618658097711785492504343953926634992332820282019728.792003956564819968"
+        )
+        .is_err());
+        assert!(parse_decimal("-1e-19").is_err());
+        assert_eq!(
+            parse_decimal_rounded("-1.0000000000000000015", Rounding::HalfUp).unwrap(),
+            I256::new(-1_000_000_000_000_000_002)
+        );
+        assert_eq!(
+            parse_decimal_rounded("-1e-19", Rounding::Down).unwrap(),
+            I256::ZERO
+        );
+
+        assert_eq!(
+            format_decimal(I256::new(-1_250_000_000_000_000_000), 18),
+            "-1.25"
+        );
+        assert_eq!(format_decimal(I256::new(-1), 17), "0");
+        assert_eq!(format_decimal(I256::new(-1), 18), "-0.000000000000000001");
+        assert_eq!(
+            format_decimal(MIN_SD59X18, 18),
+            "-57896044618658097711785492504343953926634992332820282019728.792003956564819968"
+        );
+    }
 }
@@ -0,0 +1,118 @@
+use crate::{
+    aggregate::{
+        deviation, filter_max_deviation, filtered_median, median, trimmed_mean, weighted_median,
+        AggregateError,
+    },
+    common::exp10,
+    U256,
+};
+use cosmwasm_std::StdError;
+use proptest::{prop_assert, prop_assert_eq, proptest};
+use rstest::*;
+
+fn values(values: &[u128]) -> Vec<U256> {
+    values.iter().copied().map(U256::new).collect()
+}
+
+fn not_enough(required: usize, available: usize) -> StdError {
+    AggregateError::NotEnoughSources {
+        required,
+        available,
+    }
+    .into()
+}
+
+#[rstest]
+#[case(&[5], 5)]
+#[case(&[3, 1, 2], 2)]
+#[case(&[4, 1, 3, 2], 2)]
+#[case(&[10, 1, 11, 2], 6)]
+#[case(&[7, 7, 1, 7], 7)]
+fn test_median(#[case] input: &[u128], #[case] expected: u128) {
+    assert_eq!(median(&values(input)).unwrap(), expected);
+}
+
+#[test]
+fn test_median_large() {
+    assert_eq!(median(&[U256::MAX, U256::MAX - 2]).unwrap(), U256::MAX - 1);
+    assert_eq!(median(&[]).unwrap_err(), not_enough(1, 0));
+}
+
+#[rstest]
+#[case(&[(1, 1), (2, 1), (3, 1)], 2)]
+#[case(&[(3, 1), (1, 1), (2, 1), (4, 1)], 2)]
+#[case(&[(1, 1), (2, 1), (3, 10)], 3)]
+#[case(&[(1, 5), (2, 0), (3, 5)], 1)]
+#[case(&[(2, 0), (1, 3)], 1)]
+fn test_weighted_median(#[case] input: &[(u128, u128)], #[case] expected: u128) {
+    let input: Vec<(U256, U256)> = input
+        .iter()
+        .map(|(v, w)| (U256::new(*v), U256::new(*w)))
+        .collect();
+    assert_eq!(weighted_median(&input).unwrap(), expected);
+}
+
+#[test]
+fn test_weighted_median_errors() {
+    assert_eq!(weighted_median(&[]).unwrap_err(), not_enough(1, 0));
+    assert_eq!(
+        weighted_median(&[(U256::ONE, U256::ZERO)]).unwrap_err(),
+        AggregateError::ZeroWeight.into()
+    );
+}
+
+#[rstest]
+#[case(&[1, 2, 3, 4, 100], 1, 3)]
+#[case(&[1, 2, 3, 4, 100], 0, 22)]
+#[case(&[100, 1, 5, 5, 5, 0], 2, 5)]
+fn test_trimmed_mean(#[case] input: &[u128], #[case] trim: usize, #[case] expected: u128) {
+    assert_eq!(trimmed_mean(&values(input), trim).unwrap(), expected);
+}
+
+#[test]
+fn test_trimmed_mean_errors() {
+    assert_eq!(
+        trimmed_mean(&values(&[1, 2, 3, 4]), 2).unwrap_err(),
+        not_enough(5, 4)
+    );
+    assert_eq!(trimmed_mean(&[U256::MAX, U256::MAX], 0).unwrap(), U256::MAX);
+}
+
+#[test]
+fn test_filtered_median() {
+    let prices = values(&[100, 101, 99, 150, 50]);
+    // 2% around the median of 100.
+    let max_deviation = exp10(16) * 2;
+    assert_eq!(
+        filter_max_deviation(&prices, U256::new(100), max_deviation).unwrap(),
+        values(&[100, 101, 99])
+    );
+    assert_eq!(filtered_median(&prices, max_deviation, 3).unwrap(), 100);
+    assert_eq!(
+        filtered_median(&prices, max_deviation, 4).unwrap_err(),
+        not_enough(4, 3)
+    );
+    assert_eq!(
+        deviation(U256::new(150), U256::new(100)).unwrap(),
+        exp10(17) * 5
+    );
+}
+
+proptest! {
+    #[test]
+    fn proptest_order_independent(
+        input in proptest::collection::vec(0..u64::MAX as u128, 1..16),
+        rotation in 0usize..16,
+    ) {
+        let input = values(&input);
+        let mut rotated = input.clone();
+        rotated.rotate_left(rotation % input.len());
+        rotated.reverse();
+        prop_assert_eq!(median(&input).unwrap(), median(&rotated).unwrap());
+        prop_assert_eq!(trimmed_mean(&input, input.len() / 3).unwrap(), trimmed_mean(&rotated, input.len() / 3).unwrap());
+        let weighted: Vec<_> = input.iter().map(|v| (*v, U256::ONE)).collect();
+        let median = median(&input).unwrap();
+        let weighted_median = weighted_median(&weighted).unwrap();
+        prop_assert!(weighted_median <= median);
+    }
+}
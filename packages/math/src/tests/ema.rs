@@ -0,0 +1,102 @@
+use crate::{
+    ema::{Ema, EmaMode, TAYLOR_MAX_RATIO},
+    ud60x18::{self, UNIT},
+    U256,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use proptest::prelude::*;
+use rstest::*;
+
+fn assert_close(actual: U256, expected: U256, tolerance: U256) {
+    let diff = if actual > expected {
+        actual - expected
+    } else {
+        expected - actual
+    };
+    assert!(diff <= tolerance, "{} != {}", actual, expected);
+}
+
+#[rstest]
+#[case(EmaMode::Exact)]
+#[case(EmaMode::Taylor)]
+fn test_update(#[case] mode: EmaMode) {
+    let mut ema = Ema::new(100, mode).unwrap();
+    assert_eq!(ema.value, None);
+    assert_eq!(ema.update(UNIT, 1_000).unwrap(), UNIT);
+    // No time passed, no change.
+    assert_eq!(ema.update(U256::ZERO, 0).unwrap(), UNIT);
+    // After tau seconds, 1 / e of the old value remains.
+    let expected = ud60x18::div(UNIT, ud60x18::exp(UNIT).unwrap()).unwrap();
+    assert_close(
+        ema.update(U256::ZERO, 100).unwrap(),
+        expected,
+        U256::new(10),
+    );
+    // Long gaps take the new value.
+    assert_eq!(ema.update(UNIT * 5, 10_000).unwrap(), UNIT * 5);
+}
+
+#[rstest]
+#[case(EmaMode::Exact, U256::new(100_000_000_000))]
+// A single 0.1 tau step is at the edge of the series' accuracy.
+#[case(EmaMode::Taylor, U256::new(10_000_000_000_000))]
+fn test_updates_compose(#[case] mode: EmaMode, #[case] tolerance: U256) {
+    let mut once = Ema::new(600, mode).unwrap();
+    let mut often = once;
+    once.update(UNIT * 2, 0).unwrap();
+    often.update(UNIT * 2, 0).unwrap();
+    once.update(UNIT, 60).unwrap();
+    for _ in 0..60 {
+        often.update(UNIT, 1).unwrap();
+    }
+    assert_close(often.value.unwrap(), once.value.unwrap(), tolerance);
+}
+
+#[test]
+fn test_taylor_alpha() {
+    let exact = Ema::new(1_000, EmaMode::Exact).unwrap();
+    let taylor = Ema::new(1_000, EmaMode::Taylor).unwrap();
+    assert_eq!(taylor.alpha(0).unwrap(), U256::ZERO);
+    assert_eq!(exact.alpha(0).unwrap(), U256::ZERO);
+    // The third-order series is within x^4 / 24 of the exact value.
+    for dt in [1, 10, 50, 100] {
+        let x = U256::from(dt) * UNIT / 1_000;
+        let bound = x * x / UNIT * x / UNIT * x / UNIT / 24 + 10;
+        assert_close(taylor.alpha(dt).unwrap(), exact.alpha(dt).unwrap(), bound);
+    }
+    // Past the ratio limit, both modes agree.
+    let dt = (TAYLOR_MAX_RATIO * 1_000 / UNIT).as_u64() + 1;
+    assert_eq!(taylor.alpha(dt).unwrap(), exact.alpha(dt).unwrap());
+    assert_eq!(exact.alpha(u64::MAX).unwrap(), UNIT);
+}
+
+#[test]
+fn test_borsh() {
+    let mut ema = Ema::new(30, EmaMode::Taylor).unwrap();
+    ema.update(UNIT * 3, 0).unwrap();
+    let bytes = ema.try_to_vec().unwrap();
+    assert_eq!(Ema::try_from_slice(&bytes).unwrap(), ema);
+}
+
+#[test]
+fn test_zero_tau() {
+    assert!(Ema::new(0, EmaMode::Exact).is_err());
+}
+
+proptest! {
+    #[test]
+    fn ema_stays_between_average_and_value(
+        start in 0u128..u128::MAX,
+        value in 0u128..u128::MAX,
+        dt in 0u64..100_000,
+        tau in 1u64..10_000,
+        taylor: bool,
+    ) {
+        let mode = if taylor { EmaMode::Taylor } else { EmaMode::Exact };
+        let mut ema = Ema::new(tau, mode).unwrap();
+        let (start, value) = (U256::new(start), U256::new(value));
+        ema.update(start, 0).unwrap();
+        let average = ema.update(value, dt).unwrap();
+        prop_assert!(average >= start.min(value) && average <= start.max(value));
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{
+    common::{exp10, SECONDS_PER_YEAR},
+    traits::{Rebase, RebaseHistory, SimpleRebase},
+    I256, U256,
+};
+
+const HALF_YEAR: u64 = SECONDS_PER_YEAR / 2;
+
+/// Records an exchange rate of 1 at time 0, 1.1 after half a year and 1.2 after a year.
+fn history(capacity: u32) -> RebaseHistory {
+    let mut history = RebaseHistory::new(capacity).unwrap();
+    let mut rebase = SimpleRebase::new(U256::new(1000), U256::new(1000));
+    history.record(0, &rebase).unwrap();
+    rebase.accrue(U256::new(100)).unwrap();
+    history.record(HALF_YEAR, &rebase).unwrap();
+    rebase.accrue(U256::new(100)).unwrap();
+    history.record(SECONDS_PER_YEAR, &rebase).unwrap();
+    history
+}
+
+fn assert_close(actual: I256, expected: I256) {
+    assert!(
+        (actual - expected).abs() < I256::new(1_000_000_000),
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn test_apr_apy() {
+    let history = history(8);
+    let rate = |x: i128| I256::new(x) * I256::new(exp10(16).as_i128());
+    assert_eq!(history.apr(0, SECONDS_PER_YEAR).unwrap(), rate(20));
+    assert_eq!(history.apr(0, HALF_YEAR).unwrap(), rate(20));
+    // Between checkpoints the earlier checkpoint is in effect.
+    assert_eq!(history.apr(1, SECONDS_PER_YEAR + 1).unwrap(), rate(20));
+    assert_close(history.apy(0, SECONDS_PER_YEAR).unwrap(), rate(20));
+    assert_close(history.apy(0, HALF_YEAR).unwrap(), rate(21));
+    assert!(history.apr(0, 0).is_err());
+}
+
+#[test]
+fn test_negative_apr() {
+    let mut history = history(8);
+    let mut rebase = SimpleRebase::new(U256::new(1200), U256::new(1000));
+    rebase.slash(U256::new(600)).unwrap();
+    history.record(SECONDS_PER_YEAR * 2, &rebase).unwrap();
+    let half = I256::new(exp10(17).as_i128()) * 5;
+    assert_eq!(
+        history.apr(SECONDS_PER_YEAR, SECONDS_PER_YEAR * 2).unwrap(),
+        -half
+    );
+    assert_close(
+        history.apy(SECONDS_PER_YEAR, SECONDS_PER_YEAR * 2).unwrap(),
+        -half,
+    );
+}
+
+#[test]
+fn test_twap() {
+    let history = history(8);
+    assert_eq!(history.twap(0, SECONDS_PER_YEAR).unwrap(), exp10(16) * 105);
+    // The latest rate holds after the last checkpoint.
+    assert_eq!(
+        history.twap(0, SECONDS_PER_YEAR * 2).unwrap(),
+        exp10(15) * 1125
+    );
+    assert_eq!(
+        history
+            .twap(SECONDS_PER_YEAR, SECONDS_PER_YEAR + 1)
+            .unwrap(),
+        exp10(17) * 12
+    );
+    assert!(history.twap(1, 1).is_err());
+}
+
+#[test]
+fn test_ring_buffer() {
+    let mut history = history(2);
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.oldest().unwrap().timestamp, HALF_YEAR);
+    assert_eq!(history.latest().unwrap().timestamp, SECONDS_PER_YEAR);
+    assert!(history.twap(0, SECONDS_PER_YEAR).is_err());
+    assert!(history.apr(0, SECONDS_PER_YEAR).is_err());
+
+    // A checkpoint at the latest time replaces it, and older checkpoints are rejected.
+    let rebase = SimpleRebase::new(U256::new(1300), U256::new(1000));
+    history.record(SECONDS_PER_YEAR, &rebase).unwrap();
+    assert_eq!(history.len(), 2);
+    assert_eq!(history.latest().unwrap().elastic, 1300);
+    assert!(history.record(HALF_YEAR, &rebase).is_err());
+
+    history.record(SECONDS_PER_YEAR + 1, &rebase).unwrap();
+    let timestamps: Vec<u64> = history.iter().map(|c| c.timestamp).collect();
+    assert_eq!(timestamps, vec![SECONDS_PER_YEAR, SECONDS_PER_YEAR + 1]);
+    assert!(RebaseHistory::new(0).is_err());
+}
+
+#[test]
+fn test_deserialize_invalid() {
+    let json = serde_json::to_string(&history(2)).unwrap();
+    assert_eq!(
+        serde_json::from_str::<RebaseHistory>(&json).unwrap(),
+        history(2)
+    );
+    // A head past the checkpoints errors when loading instead of panicking when iterating.
+    let json = json.replace(r#""head":1"#, r#""head":7"#);
+    assert!(serde_json::from_str::<RebaseHistory>(&json).is_err());
+}
@@ -0,0 +1,107 @@
+use crate::{
+    common::exp10,
+    traits::{LedgerMove, RebaseLedger, SimpleRebase},
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use cosmwasm_std::StdError;
+use ethnum::U256;
+
+fn ledger() -> RebaseLedger<String> {
+    let mut ledger = RebaseLedger::new();
+    // 2 elastic per base.
+    *ledger.bucket_mut("a".to_string()) = SimpleRebase::new(U256::new(200), U256::new(100));
+    // 1 elastic per 3 base.
+    *ledger.bucket_mut("b".to_string()) = SimpleRebase::new(U256::new(100), U256::new(300));
+    ledger
+}
+
+#[test]
+fn test_move_base() {
+    let mut ledger = ledger();
+    let moved = ledger
+        .move_base(&"a".to_string(), "b".to_string(), 10u128)
+        .unwrap();
+    assert_eq!(
+        moved,
+        LedgerMove {
+            base_out: U256::new(10),
+            elastic: U256::new(20),
+            base_in: U256::new(60),
+        }
+    );
+    assert_eq!(
+        ledger.get(&"a".to_string()),
+        Some(&SimpleRebase::new(U256::new(180), U256::new(90)))
+    );
+    assert_eq!(
+        ledger.get(&"b".to_string()),
+        Some(&SimpleRebase::new(U256::new(120), U256::new(360)))
+    );
+    assert_eq!(ledger.total_elastic().unwrap(), 300);
+    assert_eq!(ledger.total_base().unwrap(), 450);
+
+    // Moving into a new bucket creates it.
+    let moved = ledger
+        .move_base(&"b".to_string(), "c".to_string(), 30u128)
+        .unwrap();
+    assert_eq!(
+        (moved.elastic, moved.base_in),
+        (U256::new(10), U256::new(10))
+    );
+    assert_eq!(
+        ledger.get(&"c".to_string()),
+        Some(&SimpleRebase::new(U256::new(10), U256::new(10)))
+    );
+}
+
+#[test]
+fn test_move_base_errors() {
+    let mut ledger = ledger();
+    let before = ledger.clone();
+    assert!(matches!(
+        ledger.move_base(&"z".to_string(), "a".to_string(), 1u128),
+        Err(StdError::NotFound { .. })
+    ));
+    assert!(ledger
+        .move_base(&"a".to_string(), "a".to_string(), 1u128)
+        .is_err());
+    assert!(ledger
+        .move_base(&"a".to_string(), "b".to_string(), 101u128)
+        .is_err());
+    assert_eq!(ledger, before);
+}
+
+#[test]
+fn test_exchange_rates() {
+    let mut ledger = ledger();
+    ledger.bucket_mut("empty".to_string());
+    assert_eq!(
+        ledger.exchange_rate(&"a".to_string()).unwrap(),
+        exp10(18) * 2
+    );
+    assert!(ledger.exchange_rate(&"z".to_string()).is_err());
+    let rates = ledger.exchange_rates().unwrap();
+    assert_eq!(
+        rates.into_iter().collect::<Vec<_>>(),
+        vec![
+            ("a".to_string(), exp10(18) * 2),
+            ("b".to_string(), exp10(18) / 3),
+            ("empty".to_string(), exp10(18)),
+        ]
+    );
+}
+
+#[test]
+fn test_serialization() {
+    let ledger = ledger();
+    let bytes = ledger.try_to_vec().unwrap();
+    assert_eq!(
+        RebaseLedger::<String>::try_from_slice(&bytes).unwrap(),
+        ledger
+    );
+    let json = serde_json::to_string(&ledger).unwrap();
+    assert_eq!(
+        serde_json::from_str::<RebaseLedger<String>>(&json).unwrap(),
+        ledger
+    );
+}
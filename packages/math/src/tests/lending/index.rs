@@ -0,0 +1,106 @@
+use super::percent;
+use crate::{
+    common::{abs_diff, SECONDS_PER_YEAR},
+    lending::{
+        index::{
+            accrue_index, apr_to_apy, apr_to_apy_continuous, apy_to_apr, growth_factor, Accrual,
+        },
+        rate_model::per_second,
+    },
+    ud60x18::UNIT,
+    U256,
+};
+use proptest::prelude::*;
+use rstest::*;
+
+/// e^0.1
+const E_TENTH: U256 = U256::new(1_105_170_918_075_647_624);
+
+fn assert_close(actual: U256, expected: U256, tolerance: U256) {
+    assert!(
+        abs_diff(actual, expected) <= tolerance,
+        "{} != {}",
+        actual,
+        expected
+    );
+}
+
+#[rstest]
+// per_second rounds the rate down, so a year at 10% is slightly less than e^0.1.
+#[case(Accrual::Exact, U256::new(100_000_000))]
+#[case(Accrual::Binomial { terms: 3 }, U256::new(5_000_000_000_000))]
+fn test_growth_factor(#[case] accrual: Accrual, #[case] tolerance: U256) {
+    let rate = per_second(percent(10));
+    let factor = growth_factor(rate, SECONDS_PER_YEAR, accrual).unwrap();
+    assert_close(factor, E_TENTH, tolerance);
+    assert_eq!(growth_factor(rate, 0, accrual).unwrap(), UNIT);
+    assert_eq!(growth_factor(U256::ZERO, 100, accrual).unwrap(), UNIT);
+}
+
+#[test]
+fn test_binomial() {
+    // With as many terms as periods, the expansion is exact.
+    let accrual = Accrual::Binomial { terms: 5 };
+    assert_eq!(
+        growth_factor(percent(10), 2, accrual).unwrap(),
+        percent(121)
+    );
+    assert_eq!(
+        growth_factor(percent(10), 3, accrual).unwrap(),
+        U256::new(1_331) * U256::new(1_000_000_000_000_000)
+    );
+    // The truncated expansion stays below the exact value.
+    let rate = per_second(percent(50));
+    let binomial = growth_factor(rate, SECONDS_PER_YEAR, Accrual::Binomial { terms: 2 }).unwrap();
+    let exact = growth_factor(rate, SECONDS_PER_YEAR, Accrual::Exact).unwrap();
+    assert!(binomial < exact);
+    assert!(growth_factor(rate, 1, Accrual::Binomial { terms: 0 }).is_err());
+}
+
+#[test]
+fn test_accrue_index() {
+    let rate = per_second(percent(10));
+    let index = accrue_index(UNIT * 2, rate, SECONDS_PER_YEAR, Accrual::Exact).unwrap();
+    assert_close(index, E_TENTH * 2, U256::new(100_000_000));
+    // Accruing in two steps compounds the same as one.
+    let half = SECONDS_PER_YEAR / 2;
+    let first = accrue_index(UNIT * 2, rate, half, Accrual::Exact).unwrap();
+    let second = accrue_index(first, rate, half, Accrual::Exact).unwrap();
+    assert_close(second, index, U256::new(10_000));
+}
+
+#[rstest]
+#[case(percent(10), 1, percent(10))]
+#[case(percent(10), 12, U256::new(104_713_067_441_297_241))]
+#[case(percent(10), 365, U256::new(105_155_781_616_264_952))]
+#[case(U256::ZERO, 12, U256::ZERO)]
+fn test_apr_apy(#[case] apr: U256, #[case] periods: u64, #[case] apy: U256) {
+    assert_close(apr_to_apy(apr, periods).unwrap(), apy, U256::new(1_000));
+    assert_close(apy_to_apr(apy, periods).unwrap(), apr, U256::new(1_000_000));
+}
+
+#[test]
+fn test_apr_apy_continuous() {
+    assert_close(
+        apr_to_apy_continuous(percent(10)).unwrap(),
+        E_TENTH - UNIT,
+        U256::new(1_000),
+    );
+    assert!(apr_to_apy(percent(10), 0).is_err());
+    assert!(apy_to_apr(percent(10), 0).is_err());
+    // Rates that overflow error instead of panicking.
+    assert!(apr_to_apy(U256::MAX, 1).is_err());
+    assert!(apy_to_apr(U256::MAX, 1).is_err());
+}
+
+proptest! {
+    #[test]
+    fn binomial_below_exact(rate in 0u128..100_000_000_000, dt in 0u64..100_000_000, terms in 1u8..6) {
+        let rate = U256::new(rate);
+        let binomial = growth_factor(rate, dt, Accrual::Binomial { terms }).unwrap();
+        let exact = growth_factor(rate, dt, Accrual::Exact).unwrap();
+        prop_assert!(binomial >= UNIT);
+        // Allow for rounding in exp.
+        prop_assert!(binomial <= exact + exact / 1_000_000_000_000);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    common::{exp10, Rounding},
+    lending::liquidation::{
+        account_health, asset_amount, asset_value, liquidate, max_repay, seize_amount, Collateral,
+        CollateralParams, Debt, Liquidation,
+    },
+    traits::{PriceMath, TokenMath},
+    ud60x18::UNIT,
+    U256,
+};
+use rstest::*;
+
+use super::percent;
+
+struct Asset {
+    decimals: u8,
+    /// USD price with 8 decimals.
+    price: U256,
+}
+
+impl Asset {
+    fn new(decimals: u8, dollars: u128) -> Self {
+        Self {
+            decimals,
+            price: U256::new(dollars) * exp10(8),
+        }
+    }
+}
+
+impl TokenMath for Asset {
+    const NORMALIZED_PRECISION: u8 = 18;
+    const BANKERS_ROUNDING_ENABLED: bool = false;
+
+    fn token_decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+impl PriceMath for Asset {
+    const PRICE_PRECISION: u8 = 8;
+
+    fn price(&self) -> U256 {
+        self.price
+    }
+}
+
+fn params() -> CollateralParams {
+    CollateralParams::new(percent(80), percent(85), percent(5)).unwrap()
+}
+
+fn usd(x: u128) -> U256 {
+    U256::new(x) * UNIT
+}
+
+#[test]
+fn test_account_health() {
+    let (eth, wbtc, usdc) = (
+        Asset::new(18, 2_000),
+        Asset::new(8, 30_000),
+        Asset::new(6, 1),
+    );
+    let collaterals = [
+        Collateral {
+            asset: &eth,
+            amount: exp10(18),
+            params: params(),
+        },
+        Collateral {
+            asset: &wbtc,
+            amount: exp10(7),
+            params: CollateralParams::new(percent(70), percent(75), percent(10)).unwrap(),
+        },
+    ];
+    let debts = [
+        Debt {
+            asset: &usdc,
+            amount: U256::new(3_000) * exp10(6),
+        },
+        Debt {
+            asset: &eth,
+            amount: exp10(18),
+        },
+    ];
+    let health = account_health(&collaterals, &debts).unwrap();
+    assert_eq!(health.collateral_value, usd(5_000));
+    assert_eq!(health.borrow_limit, usd(3_700));
+    assert_eq!(health.liquidation_limit, usd(3_950));
+    assert_eq!(health.debt_value, usd(5_000));
+    assert_eq!(health.health_factor().unwrap(), percent(79));
+    assert_eq!(health.ltv().unwrap(), UNIT);
+    assert!(health.is_liquidatable());
+    assert_eq!(health.available_to_borrow(), U256::ZERO);
+
+    let healthy = account_health(&collaterals, &debts[..1]).unwrap();
+    assert!(!healthy.is_liquidatable());
+    assert_eq!(healthy.available_to_borrow(), usd(700));
+    assert_eq!(healthy.ltv().unwrap(), percent(60));
+
+    let empty = account_health::<Asset>(&[], &[]).unwrap();
+    assert_eq!(empty.health_factor().unwrap(), U256::MAX);
+    assert_eq!(empty.ltv().unwrap(), U256::ZERO);
+}
+
+#[test]
+fn test_rounding_favors_protocol() {
+    // One utoken of a token with 24 decimals is worth less than the normalized precision.
+    let dust = Asset::new(24, 1);
+    assert_eq!(
+        asset_value(&dust, U256::ONE, Rounding::Down).unwrap(),
+        U256::ZERO
+    );
+    assert_eq!(
+        asset_value(&dust, U256::ONE, Rounding::Up).unwrap(),
+        U256::ONE
+    );
+    let collateral = Collateral {
+        asset: &dust,
+        amount: U256::ONE,
+        params: params(),
+    };
+    let debt = Debt {
+        asset: &dust,
+        amount: U256::ONE,
+    };
+    let health = account_health(&[collateral], &[debt]).unwrap();
+    assert_eq!(health.collateral_value, U256::ZERO);
+    assert_eq!(health.debt_value, U256::ONE);
+
+    let usdc = Asset::new(6, 3);
+    assert_eq!(
+        asset_amount(&usdc, UNIT, Rounding::Down).unwrap(),
+        U256::new(333_333)
+    );
+    assert_eq!(
+        asset_amount(&usdc, UNIT, Rounding::Up).unwrap(),
+        U256::new(333_334)
+    );
+    assert!(asset_amount(&Asset::new(6, 0), UNIT, Rounding::Down).is_err());
+}
+
+#[rstest]
+#[case(1_000, 750, 463_235_294_117_647_058)]
+#[case(100, 100, 61_764_705_882_352_941)]
+fn test_liquidate(#[case] requested: u128, #[case] repay: u128, #[case] seize: u128) {
+    let (eth, usdc) = (Asset::new(18, 1_700), Asset::new(6, 1));
+    let debt = Debt {
+        asset: &usdc,
+        amount: U256::new(1_500) * exp10(6),
+    };
+    let collateral = Collateral {
+        asset: &eth,
+        amount: exp10(18),
+        params: params(),
+    };
+    let requested = U256::new(requested) * exp10(6);
+    assert_eq!(
+        liquidate(requested, &debt, &collateral, percent(50)).unwrap(),
+        Liquidation {
+            repay: U256::new(repay) * exp10(6),
+            seize: U256::new(seize),
+        }
+    );
+}
+
+#[test]
+fn test_liquidate_all_collateral() {
+    let (eth, usdc) = (Asset::new(18, 1_700), Asset::new(6, 1));
+    let debt = Debt {
+        asset: &usdc,
+        amount: U256::new(1_500) * exp10(6),
+    };
+    let collateral = Collateral {
+        asset: &eth,
+        amount: U256::new(3) * exp10(17),
+        params: params(),
+    };
+    // 0.3 ETH is worth $510, covering 510 / 1.05 = $485.7142857 of debt rounded up.
+    assert_eq!(
+        liquidate(U256::new(750) * exp10(6), &debt, &collateral, UNIT).unwrap(),
+        Liquidation {
+            repay: U256::new(485_714_286),
+            seize: U256::new(3) * exp10(17),
+        }
+    );
+    // Rounding the repayment up means it would have bought slightly more than the collateral.
+    assert_eq!(
+        seize_amount(U256::new(485_714_286), &usdc, &eth, percent(5)).unwrap(),
+        U256::new(300_000_000_176_470_588)
+    );
+}
+
+#[test]
+fn test_invalid_params() {
+    assert!(CollateralParams::new(percent(90), percent(85), U256::ZERO).is_err());
+    assert!(CollateralParams::new(percent(90), percent(101), U256::ZERO).is_err());
+    assert!(max_repay(UNIT, UNIT + 1).is_err());
+    assert_eq!(max_repay(U256::new(3), percent(50)).unwrap(), U256::ONE);
+}
@@ -0,0 +1,11 @@
+use crate::{common::exp10, U256};
+
+mod index;
+mod liquidation;
+mod rate_model;
+mod scaled;
+
+/// `x` percent as an unsigned 60.18-decimal number.
+fn percent(x: u128) -> U256 {
+    U256::new(x) * exp10(16)
+}
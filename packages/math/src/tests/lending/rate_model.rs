@@ -0,0 +1,110 @@
+use super::percent;
+use crate::{
+    common::SECONDS_PER_YEAR,
+    lending::rate_model::{
+        per_second, utilization, InterestRateModel, JumpRateModel, LinearRateModel, SlopeRateModel,
+    },
+    ud60x18::UNIT,
+    U256,
+};
+use cosmwasm_std::StdResult;
+use rstest::*;
+
+#[rstest]
+#[case(100, 0, 0, U256::ZERO)]
+#[case(20, 80, 0, percent(80))]
+#[case(30, 80, 10, percent(80))]
+#[case(0, 80, 0, UNIT)]
+fn test_utilization(
+    #[case] cash: u128,
+    #[case] borrows: u128,
+    #[case] reserves: u128,
+    #[case] expected: U256,
+) {
+    assert_eq!(
+        utilization(U256::new(cash), U256::new(borrows), U256::new(reserves)).unwrap(),
+        expected
+    );
+}
+
+#[test]
+fn test_utilization_errors() {
+    assert!(utilization(U256::ZERO, U256::new(10), U256::new(20)).is_err());
+    assert!(utilization(U256::ZERO, U256::new(10), U256::new(10)).is_err());
+}
+
+#[test]
+fn test_per_second() {
+    assert_eq!(per_second(U256::from(SECONDS_PER_YEAR) * 7), U256::new(7));
+}
+
+#[rstest]
+#[case(U256::ZERO, percent(2))]
+#[case(percent(50), percent(7))]
+#[case(UNIT, percent(12))]
+fn test_linear(#[case] utilization: U256, #[case] expected: U256) {
+    let model = LinearRateModel::new(percent(2), percent(10));
+    assert_eq!(model.borrow_rate(utilization).unwrap(), expected);
+}
+
+#[rstest]
+#[case(U256::ZERO, percent(2))]
+#[case(percent(80), percent(10))]
+#[case(percent(90), percent(25))]
+#[case(UNIT, percent(40))]
+fn test_jump(#[case] utilization: U256, #[case] expected: U256) {
+    let model = JumpRateModel::new(percent(2), percent(10), percent(150), percent(80)).unwrap();
+    assert_eq!(model.borrow_rate(utilization).unwrap(), expected);
+}
+
+#[rstest]
+#[case(U256::ZERO, percent(1))]
+#[case(percent(40), percent(3))]
+#[case(percent(80), percent(5))]
+#[case(percent(90), percent(35))]
+#[case(UNIT, percent(65))]
+fn test_slope(#[case] utilization: U256, #[case] expected: U256) {
+    let model = SlopeRateModel::new(percent(1), percent(4), percent(60), percent(80)).unwrap();
+    assert_eq!(model.borrow_rate(utilization).unwrap(), expected);
+}
+
+#[test]
+fn test_invalid_models() {
+    assert!(JumpRateModel::new(U256::ZERO, U256::ZERO, U256::ZERO, UNIT + 1).is_err());
+    assert!(SlopeRateModel::new(U256::ZERO, U256::ZERO, U256::ZERO, U256::ZERO).is_err());
+    assert!(SlopeRateModel::new(U256::ZERO, U256::ZERO, U256::ZERO, UNIT).is_err());
+    // A model that skipped `new`, e.g. from storage, errors instead of panicking.
+    let model = SlopeRateModel {
+        base_rate: U256::ZERO,
+        slope1: U256::ZERO,
+        slope2: U256::ZERO,
+        optimal_utilization: UNIT + 1,
+    };
+    assert!(model.borrow_rate(UNIT).is_err());
+    assert!(model.borrow_rate(UNIT + 2).is_err());
+}
+
+/// A custom curve plugged in through the trait.
+struct Flat(U256);
+
+impl InterestRateModel for Flat {
+    fn borrow_rate(&self, _: U256) -> StdResult<U256> {
+        Ok(self.0)
+    }
+}
+
+#[test]
+fn test_supply_rate() {
+    let model = Flat(percent(10));
+    // 10% borrow rate, half the pool borrowed, 20% to reserves.
+    assert_eq!(
+        model.supply_rate(percent(50), percent(20)).unwrap(),
+        percent(4)
+    );
+    assert_eq!(
+        model.supply_rate(U256::ZERO, U256::ZERO).unwrap(),
+        U256::ZERO
+    );
+    assert_eq!(model.supply_rate(UNIT, UNIT).unwrap(), U256::ZERO);
+    assert!(model.supply_rate(UNIT, UNIT + 1).is_err());
+}
@@ -0,0 +1,119 @@
+use crate::{
+    common::Rounding,
+    lending::scaled::{
+        ray_div, ray_mul, ray_to_wad, wad_to_ray, ScaledBalance, ScaledKind, RAY, WAD,
+    },
+    U256,
+};
+use proptest::prelude::*;
+use rstest::*;
+
+#[test]
+fn test_wad_ray() {
+    assert_eq!(wad_to_ray(WAD).unwrap(), RAY);
+    assert!(wad_to_ray(U256::MAX).is_err());
+    assert_eq!(ray_to_wad(RAY + 1, Rounding::Down).unwrap(), WAD);
+    assert_eq!(ray_to_wad(RAY + 1, Rounding::Up).unwrap(), WAD + 1);
+    let half = RAY / 2;
+    assert_eq!(
+        ray_mul(half, U256::new(3), Rounding::Down).unwrap(),
+        U256::new(1)
+    );
+    assert_eq!(
+        ray_mul(half, U256::new(3), Rounding::Up).unwrap(),
+        U256::new(2)
+    );
+    assert_eq!(
+        ray_div(U256::new(1), RAY * 3, Rounding::Down).unwrap(),
+        U256::ZERO
+    );
+    assert_eq!(
+        ray_div(U256::new(1), RAY * 3, Rounding::Up).unwrap(),
+        U256::new(1)
+    );
+}
+
+#[test]
+fn test_balance_grows_with_index() {
+    let mut balance = ScaledBalance::default();
+    assert_eq!(
+        balance
+            .mint(U256::new(1_000), RAY, ScaledKind::Supply)
+            .unwrap(),
+        U256::new(1_000)
+    );
+    let index = RAY * 11 / 10;
+    assert_eq!(
+        balance.balance(index, ScaledKind::Supply).unwrap(),
+        U256::new(1_100)
+    );
+    assert_eq!(
+        balance
+            .mint(U256::new(110), index, ScaledKind::Supply)
+            .unwrap(),
+        U256::new(100)
+    );
+    assert_eq!(
+        balance
+            .burn(U256::new(550), index, ScaledKind::Supply)
+            .unwrap(),
+        U256::new(500)
+    );
+    assert_eq!(
+        balance.balance(index, ScaledKind::Supply).unwrap(),
+        U256::new(660)
+    );
+}
+
+#[rstest]
+#[case(ScaledKind::Supply, 2, 3)]
+#[case(ScaledKind::Debt, 3, 5)]
+fn test_rounding_favors_protocol(
+    #[case] kind: ScaledKind,
+    #[case] scaled: u128,
+    #[case] balance: u128,
+) {
+    // 4 / 1.5 = 2.67 scaled, worth 4 again only if nothing is rounded away.
+    let index = RAY * 3 / 2;
+    let mut account = ScaledBalance::default();
+    account.mint(U256::new(4), index, kind).unwrap();
+    assert_eq!(account.scaled, U256::new(scaled));
+    assert_eq!(account.balance(index, kind).unwrap(), U256::new(balance));
+}
+
+#[rstest]
+#[case(ScaledKind::Supply)]
+#[case(ScaledKind::Debt)]
+fn test_burn_all(#[case] kind: ScaledKind) {
+    let index = RAY * 7 / 3;
+    let mut account = ScaledBalance::new(U256::new(1_000_003));
+    let balance = account.balance(index, kind).unwrap();
+    assert!(account.burn(balance + 1, index, kind).is_err());
+    assert_eq!(
+        account.burn(balance, index, kind).unwrap(),
+        U256::new(1_000_003)
+    );
+    assert_eq!(account.scaled, U256::ZERO);
+    assert!(account.mint(U256::new(1), U256::ZERO, kind).is_err());
+}
+
+proptest! {
+    #[test]
+    fn supply_never_pays_out_more(amount in 1u128..u128::MAX, index in 0u128..u128::MAX, partial in 0u128..u128::MAX) {
+        let index = RAY + U256::new(index) % (RAY * 100);
+        let amount = U256::new(amount);
+        let mut account = ScaledBalance::default();
+        account.mint(amount, index, ScaledKind::Supply).unwrap();
+        prop_assert!(account.balance(index, ScaledKind::Supply).unwrap() <= amount);
+
+        let mut debt = ScaledBalance::default();
+        debt.mint(amount, index, ScaledKind::Debt).unwrap();
+        prop_assert!(debt.balance(index, ScaledKind::Debt).unwrap() >= amount);
+
+        // Burning part of the balance never leaves more than the rest.
+        let balance = account.balance(index, ScaledKind::Supply).unwrap();
+        let partial = U256::new(partial) % (balance + 1);
+        account.burn(partial, index, ScaledKind::Supply).unwrap();
+        prop_assert!(account.balance(index, ScaledKind::Supply).unwrap() <= balance - partial);
+    }
+}
@@ -1,2 +1,16 @@
+mod aggregate;
+mod ema;
+mod history;
+mod lending;
+mod ledger;
+mod oracle;
 mod props;
+mod rebase;
+mod rewards;
+mod ring_buffer;
+#[cfg(feature = "abi")]
+mod schema;
+mod token;
+mod twap;
 mod ud60x18;
+mod vault;
@@ -0,0 +1,66 @@
+use crate::{
+    common::{exp10, Rounding},
+    oracle::OraclePrice,
+    U256,
+};
+use rstest::*;
+
+#[test]
+fn test_invert() {
+    let price = OraclePrice::new("ATOM", "USD", exp10(8) * 8, 8, 100);
+    let inverted = price.invert().unwrap();
+    assert_eq!(
+        inverted,
+        OraclePrice::new("USD", "ATOM", exp10(7) * 125 / 100, 8, 100)
+    );
+    assert_eq!(inverted.invert().unwrap(), price);
+
+    // 1/3 rounds down.
+    let price = OraclePrice::new("A", "B", exp10(18) * 3, 18, 0);
+    assert_eq!(price.invert().unwrap().price, exp10(18) / 3);
+    assert!(OraclePrice::new("A", "B", U256::ZERO, 18, 0)
+        .invert()
+        .is_err());
+}
+
+#[test]
+fn test_cross() {
+    let atom = OraclePrice::new("ATOM", "USD", exp10(8) * 10, 8, 100);
+    let scrt = OraclePrice::new("SCRT", "USD", exp10(17) * 5, 18, 90);
+    assert_eq!(
+        atom.cross(&scrt).unwrap(),
+        OraclePrice::new("ATOM", "SCRT", exp10(8) * 20, 8, 90)
+    );
+    assert_eq!(
+        scrt.cross(&atom).unwrap(),
+        OraclePrice::new("SCRT", "ATOM", exp10(16) * 5, 18, 90)
+    );
+
+    let eur = OraclePrice::new("USD", "EUR", exp10(8), 8, 100);
+    assert!(atom.cross(&eur).is_err());
+    let zero = OraclePrice::new("SCRT", "USD", U256::ZERO, 18, 90);
+    assert!(atom.cross(&zero).is_err());
+}
+
+#[rstest]
+#[case(8, Rounding::Down, 123_456_789)]
+#[case(6, Rounding::Down, 1_234_567)]
+#[case(6, Rounding::Up, 1_234_568)]
+#[case(6, Rounding::HalfUp, 1_234_568)]
+#[case(4, Rounding::HalfEven, 12_346)]
+#[case(10, Rounding::Down, 12_345_678_900)]
+fn test_rescale(#[case] precision: u8, #[case] rounding: Rounding, #[case] expected: u128) {
+    // 1.23456789
+    let price = OraclePrice::new("A", "B", 123_456_789u128, 8, 0);
+    let rescaled = price.rescale(precision, rounding).unwrap();
+    assert_eq!(rescaled.price, expected);
+    assert_eq!(rescaled.precision, precision);
+    assert_eq!((rescaled.base, rescaled.quote), (price.base, price.quote));
+}
+
+#[test]
+fn test_rescale_errors() {
+    let price = OraclePrice::new("A", "B", U256::MAX, 8, 0);
+    assert!(price.rescale(9, Rounding::Down).is_err());
+    assert!(price.rescale(100, Rounding::Down).is_err());
+}
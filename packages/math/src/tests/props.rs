@@ -2,7 +2,12 @@ use cosmwasm_std::Uint256;
 use ethnum::{I256, U256};
 use proptest::{proptest, strategy::Strategy};
 
-use crate::common::muldiv;
+use crate::{
+    common::{muldiv, Rounding},
+    sd59x18,
+    traits::{Rebase, SimpleRebase, VirtualRebase},
+    ud60x18,
+};
 
 fn arb_xyz(max_x: u128, max_y: u128, max_z: u128) -> impl Strategy<Value = (U256, U256, U256)> {
     (0..max_x, 0..max_y, 1..max_z).prop_map(|(x, y, z)| {
@@ -45,4 +50,84 @@ proptest! {
         let z = x - y;
         assert_eq!(c.as_i128(), z);
     }
+
+    #[test]
+    fn proptest_ud60x18_decimal_roundtrip(hi in proptest::num::u128::ANY, lo in proptest::num::u128::ANY) {
+        let x = U256::from_words(hi, lo);
+        let s = ud60x18::format_decimal(x, 18);
+        assert_eq!(ud60x18::parse_decimal(&s).unwrap(), x);
+    }
+
+    #[test]
+    fn proptest_ud60x18_decimal_truncated(x in proptest::num::u128::ANY, decimals in 0u8..18) {
+        let x = U256::from(x);
+        let s = ud60x18::format_decimal(x, decimals);
+        let precision = crate::common::exp10(18 - decimals);
+        assert_eq!(ud60x18::parse_decimal(&s).unwrap(), x / precision * precision);
+        // Rounding the full representation down to the same number of decimals agrees with truncation.
+        let scientific = format!("{}e-{}", x, 18 + 18 - decimals);
+        assert_eq!(ud60x18::parse_decimal_rounded(&scientific, Rounding::Down).unwrap(), x / precision);
+    }
+
+    #[test]
+    fn proptest_sd59x18_decimal_roundtrip(hi in proptest::num::i128::ANY, lo in proptest::num::u128::ANY) {
+        let x = I256::from_words(hi, lo as i128);
+        let s = sd59x18::format_decimal(x, 18);
+        assert_eq!(sd59x18::parse_decimal(&s).unwrap(), x);
+    }
+
+    #[test]
+    fn proptest_rebase_accrue_slash(
+        elastic in 0..u64::MAX as u128,
+        base in 1..u64::MAX as u128,
+        delta in 0..u64::MAX as u128,
+        amount in 0..u64::MAX as u128,
+    ) {
+        let mut rebase = SimpleRebase::new(U256::new(elastic), U256::new(base));
+        let before = rebase.to_elastic(amount, Rounding::Down).unwrap();
+        rebase.accrue(delta).unwrap();
+        assert_eq!((rebase.elastic, rebase.base), (U256::new(elastic + delta), U256::new(base)));
+        assert!(rebase.to_elastic(amount, Rounding::Down).unwrap() >= before);
+
+        rebase.slash(delta).unwrap();
+        assert_eq!((rebase.elastic, rebase.base), (U256::new(elastic), U256::new(base)));
+        assert_eq!(rebase.to_elastic(amount, Rounding::Down).unwrap(), before);
+        assert!(rebase.slash(elastic + 1).is_err());
+    }
+
+    #[test]
+    fn proptest_rebase_accrue_with_fee(
+        elastic in 1..u64::MAX as u128,
+        base in 1..u64::MAX as u128,
+        yield_elastic in 0..u64::MAX as u128,
+        fee_rate in 0..1_000_000_000_000_000_000u128,
+        offset in 0..6u8,
+    ) {
+        let mut rebase = VirtualRebase::new(U256::new(elastic), U256::new(base), offset).unwrap();
+        let before = rebase.to_elastic(base, Rounding::Down).unwrap();
+        let (_, fee_base) = rebase.accrue_with_fee(yield_elastic, fee_rate).unwrap();
+        assert_eq!(rebase.elastic, elastic + yield_elastic);
+        assert_eq!(rebase.base, base + fee_base);
+
+        // The fee recipient gets at most its share of the yield, and existing base keeps at least its prior value.
+        let fee_elastic = muldiv(U256::new(yield_elastic), U256::new(fee_rate), ud60x18::UNIT).unwrap();
+        assert!(rebase.to_elastic(fee_base, Rounding::Down).unwrap() <= fee_elastic);
+        assert!(rebase.to_elastic(base, Rounding::Down).unwrap() >= before);
+    }
+
+    #[test]
+    fn proptest_rebase_accrue_with_fee_unchanged_on_err(
+        elastic in (0..4u128, 0..u128::MAX),
+        base in (0..4u128, 0..u128::MAX),
+        yield_elastic in (0..4u128, 0..u128::MAX),
+        fee_rate in 0..1_000_000_000_000_000_001u128,
+    ) {
+        // Values near U256::MAX make the additions and the fee muldiv overflow.
+        let high = |(hi, lo): (u128, u128)| if hi == 0 { U256::new(lo) } else { U256::from_words(u128::MAX - hi + 1, lo) };
+        let mut rebase = SimpleRebase::new(high(elastic), high(base));
+        let before = rebase.clone();
+        if rebase.accrue_with_fee(high(yield_elastic), fee_rate).is_err() {
+            assert_eq!(rebase, before);
+        }
+    }
 }
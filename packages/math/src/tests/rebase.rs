@@ -0,0 +1,198 @@
+use crate::{
+    common::{exp10, Rounding},
+    traits::{Rebase, SimpleRebase, VirtualRebase},
+};
+use cosmwasm_std::Uint256;
+use ethnum::U256;
+use rstest::*;
+
+/// Runs the first depositor attack: the attacker deposits 1 elastic, donates `donation` elastic directly, then a
+/// victim deposits `deposit`. Returns the victim's base and the elastic each party can redeem afterwards.
+fn inflation_attack(mut rebase: impl Rebase, donation: U256, deposit: U256) -> (U256, U256, U256) {
+    let (_, attacker_base) = rebase.add_elastic(U256::ONE, false).unwrap();
+    rebase.set_elastic(rebase.elastic() + donation);
+    let (_, victim_base) = rebase.add_elastic(deposit, false).unwrap();
+    let attacker_elastic = rebase.to_elastic(attacker_base, false).unwrap();
+    let victim_elastic = rebase.to_elastic(victim_base, false).unwrap();
+    (victim_base, attacker_elastic, victim_elastic)
+}
+
+#[test]
+fn test_inflation_attack_simple() {
+    let deposit = exp10(18);
+    let (victim_base, attacker_elastic, victim_elastic) =
+        inflation_attack(SimpleRebase::default(), deposit, deposit);
+    // The victim's deposit rounds down to zero base and the attacker takes all of it.
+    assert_eq!(victim_base, 0);
+    assert_eq!(victim_elastic, 0);
+    assert_eq!(attacker_elastic, deposit * 2 + 1);
+}
+
+#[rstest]
+#[case(0)]
+#[case(3)]
+#[case(6)]
+fn test_inflation_attack_virtual(#[case] decimals_offset: u8) {
+    let deposit = exp10(18);
+    let spent = deposit + 1;
+    let (victim_base, attacker_elastic, victim_elastic) = inflation_attack(
+        VirtualRebase::new(U256::ZERO, U256::ZERO, decimals_offset).unwrap(),
+        deposit,
+        deposit,
+    );
+    assert!(victim_base > 0);
+    // Most of the donation is captured by the virtual shares, so the attack loses money.
+    assert!(attacker_elastic < spent);
+    // The victim loses less than a 1 / (1 + 10^offset) share of the deposit.
+    let max_loss = deposit / (exp10(decimals_offset) + 1) + 1;
+    assert!(deposit - victim_elastic <= max_loss);
+}
+
+#[test]
+fn test_virtual_conversions() {
+    let mut rebase = VirtualRebase::new(U256::ZERO, U256::ZERO, 3).unwrap();
+    assert_eq!(rebase.to_base(U256::new(5), false).unwrap(), 5_000);
+    let (_, base) = rebase.add_elastic(U256::new(100), false).unwrap();
+    assert_eq!(base, 100_000);
+    assert_eq!(rebase.to_elastic(base, false).unwrap(), 100);
+    let (_, elastic) = rebase.sub_base(base, false).unwrap();
+    assert_eq!(elastic, 100);
+    assert_eq!((rebase.elastic, rebase.base), (U256::ZERO, U256::ZERO));
+    // Converting more base than exists must not underflow.
+    let mut rebase = VirtualRebase::new(U256::new(100), U256::ZERO, 3).unwrap();
+    assert!(rebase.sub_elastic(U256::new(100), false).is_err());
+    assert_eq!((rebase.elastic(), rebase.base()), (U256::new(100), U256::ZERO));
+    assert!(rebase.sub_base(U256::ONE, false).is_err());
+    assert_eq!((rebase.elastic(), rebase.base()), (U256::new(100), U256::ZERO));
+}
+
+#[test]
+fn test_virtual_decimals_offset_too_large() {
+    assert!(VirtualRebase::new(U256::ZERO, U256::ZERO, 77).is_ok());
+    assert!(VirtualRebase::new(U256::ZERO, U256::ZERO, 78).is_err());
+    // An offset that skipped `new`, e.g. from storage, errors instead of panicking.
+    let mut rebase = VirtualRebase {
+        elastic: U256::new(100),
+        base: U256::new(100),
+        decimals_offset: u8::MAX,
+    };
+    assert!(rebase.to_base(U256::ONE, false).is_err());
+    assert!(rebase.to_elastic(U256::ONE, false).is_err());
+    assert!(rebase.add_elastic(U256::ONE, false).is_err());
+    assert_eq!((rebase.elastic, rebase.base), (U256::new(100), U256::new(100)));
+}
+
+#[rstest]
+#[case(Rounding::Down, 6, 15)]
+#[case(Rounding::Up, 7, 15)]
+#[case(Rounding::HalfUp, 7, 15)]
+#[case(Rounding::HalfEven, 7, 15)]
+#[case(false, 6, 15)]
+#[case(true, 7, 15)]
+fn test_rounding(
+    #[case] rounding: impl Into<Rounding> + Copy,
+    #[case] base: u128,
+    #[case] elastic: u128,
+) {
+    // 150 elastic backing 100 base.
+    let rebase = SimpleRebase::new(U256::new(150), U256::new(100));
+    assert_eq!(rebase.to_base(U256::new(10), rounding).unwrap(), base);
+    assert_eq!(rebase.to_elastic(U256::new(10), rounding).unwrap(), elastic);
+}
+
+#[test]
+fn test_to_base_checked() {
+    let mut rebase = SimpleRebase::new(U256::new(1000), U256::new(1));
+    assert_eq!(rebase.to_base(U256::new(999), Rounding::Down).unwrap(), 0);
+    assert!(rebase
+        .to_base_checked(U256::new(999), Rounding::Down)
+        .is_err());
+    assert_eq!(
+        rebase
+            .to_base_checked(U256::new(999), Rounding::Up)
+            .unwrap(),
+        1
+    );
+    assert_eq!(
+        rebase.to_base_checked(U256::ZERO, Rounding::Down).unwrap(),
+        0
+    );
+
+    assert!(rebase
+        .add_elastic_checked(U256::new(999), Rounding::Down)
+        .is_err());
+    assert_eq!((rebase.elastic, rebase.base), (U256::new(1000), U256::ONE));
+    let (_, base) = rebase
+        .add_elastic_checked(U256::new(2000), Rounding::Down)
+        .unwrap();
+    assert_eq!(base, 2);
+    assert_eq!(
+        (rebase.elastic, rebase.base),
+        (U256::new(3000), U256::new(3))
+    );
+}
+
+#[test]
+fn test_accrue_with_fee() {
+    let mut rebase = SimpleRebase::new(U256::new(1000), U256::new(1000));
+    // 10% of 100 yield is 10 elastic, which is worth 10 * 1000 / (1100 - 10) base.
+    let (_, fee_base) = rebase.accrue_with_fee(U256::new(100), exp10(17)).unwrap();
+    assert_eq!(fee_base, 9);
+    assert_eq!((rebase.elastic, rebase.base), (U256::new(1100), U256::new(1009)));
+    assert_eq!(rebase.to_elastic(fee_base, Rounding::Down).unwrap(), 9);
+
+    let (_, fee_base) = rebase.accrue_with_fee(U256::new(100), U256::ZERO).unwrap();
+    assert_eq!(fee_base, 0);
+    assert_eq!(rebase.elastic, 1200);
+
+    assert!(rebase.accrue_with_fee(U256::new(100), exp10(18) + 1).is_err());
+    assert_eq!(rebase.elastic, 1200);
+
+    // A fee that would take all elastic from existing base fails without accruing the yield.
+    let mut rebase = SimpleRebase::new(U256::ZERO, U256::new(100));
+    assert!(rebase.accrue_with_fee(U256::new(100), exp10(18)).is_err());
+    assert_eq!((rebase.elastic, rebase.base), (U256::ZERO, U256::new(100)));
+}
+
+#[derive(Rebase, Default)]
+struct DerivedRebase {
+    #[rebase(elastic)]
+    assets: U256,
+    #[rebase(base)]
+    shares: Uint256,
+    #[allow(dead_code)]
+    owner: String,
+}
+
+#[test]
+fn test_derive() {
+    let mut rebase = DerivedRebase::default();
+    rebase.add_elastic(U256::new(100), Rounding::Down).unwrap();
+    rebase.accrue(U256::new(50)).unwrap();
+    assert_eq!(rebase.assets, 150);
+    assert_eq!(rebase.shares, Uint256::from(100u128));
+    assert_eq!(rebase.elastic_uint256(), Uint256::from(150u128));
+    assert_eq!(rebase.base(), 100);
+    assert_eq!(rebase.to_elastic(U256::new(10), Rounding::Down).unwrap(), 15);
+}
+
+/// Field types behind an alias are converted the same way as spelled-out ones.
+type Shares = Uint256;
+
+#[derive(Rebase, Default)]
+struct AliasedRebase {
+    #[rebase(elastic)]
+    assets: Shares,
+    #[rebase(base)]
+    shares: Shares,
+}
+
+#[test]
+fn test_derive_aliased() {
+    let mut rebase = AliasedRebase::default();
+    rebase.add_elastic(U256::new(100), Rounding::Down).unwrap();
+    rebase.accrue(U256::new(50)).unwrap();
+    assert_eq!(rebase.assets, Uint256::from(150u128));
+    assert_eq!(rebase.shares, Uint256::from(100u128));
+    assert_eq!(rebase.elastic(), 150);
+}
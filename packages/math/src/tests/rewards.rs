@@ -0,0 +1,176 @@
+use crate::{
+    rewards::{MultiRewardAccumulator, RewardAccumulator, RewardCheckpoint, UserRewards},
+    ud60x18::UNIT,
+    U256,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use proptest::prelude::*;
+use std::collections::BTreeMap;
+
+fn tokens(x: u128) -> U256 {
+    U256::new(x) * UNIT
+}
+
+#[test]
+fn test_stream() {
+    let mut accumulator = RewardAccumulator::new(0);
+    // 1000 tokens over 100 seconds to 10 staked tokens.
+    accumulator
+        .notify_reward(tokens(1_000), 100, 0, tokens(10))
+        .unwrap();
+    assert_eq!(accumulator.rate, tokens(10));
+    assert_eq!(
+        accumulator.reward_per_token_at(50, tokens(10)).unwrap(),
+        tokens(50)
+    );
+    // Nothing streams after the period ends.
+    assert_eq!(
+        accumulator.reward_per_token_at(500, tokens(10)).unwrap(),
+        tokens(100)
+    );
+    assert_eq!(accumulator.update(30, tokens(10)).unwrap(), tokens(30));
+    assert_eq!(accumulator.last_update, 30);
+
+    // The leftover 700 tokens roll into the next stream.
+    accumulator
+        .notify_reward(tokens(300), 50, 40, tokens(10))
+        .unwrap();
+    assert_eq!(accumulator.rate, tokens(18));
+    assert_eq!(accumulator.period_finish, 90);
+    assert_eq!(accumulator.update(200, tokens(10)).unwrap(), tokens(130));
+    assert_eq!(accumulator.last_update, 90);
+    assert!(accumulator.notify_reward(UNIT, 0, 200, tokens(10)).is_err());
+}
+
+#[test]
+fn test_nothing_staked() {
+    let mut accumulator = RewardAccumulator::new(0);
+    accumulator
+        .notify_reward(tokens(100), 100, 0, U256::ZERO)
+        .unwrap();
+    assert_eq!(accumulator.update(50, U256::ZERO).unwrap(), U256::ZERO);
+    assert_eq!(accumulator.update(100, tokens(5)).unwrap(), tokens(10));
+    assert!(accumulator.distribute(UNIT, U256::ZERO).is_err());
+}
+
+#[test]
+fn test_checkpoints() {
+    let mut accumulator = RewardAccumulator::new(0);
+    let (mut alice, mut bob) = (RewardCheckpoint::default(), RewardCheckpoint::default());
+    alice.checkpoint(U256::ZERO, U256::ZERO).unwrap();
+    // Alice stakes 1, then 100 rewards are distributed.
+    let rpt = accumulator.distribute(tokens(100), tokens(1)).unwrap();
+    // Bob stakes 3 afterwards and misses them.
+    bob.checkpoint(U256::ZERO, rpt).unwrap();
+    let rpt = accumulator.distribute(tokens(100), tokens(4)).unwrap();
+
+    assert_eq!(alice.earned(tokens(1), rpt).unwrap(), tokens(125));
+    assert_eq!(bob.earned(tokens(3), rpt).unwrap(), tokens(75));
+    // Alice doubles her stake, crediting what she earned so far.
+    assert_eq!(alice.checkpoint(tokens(1), rpt).unwrap(), tokens(125));
+    let rpt = accumulator.distribute(tokens(50), tokens(5)).unwrap();
+    assert_eq!(alice.earned(tokens(2), rpt).unwrap(), tokens(145));
+    assert_eq!(alice.claim(), tokens(125));
+    assert_eq!(alice.claim(), U256::ZERO);
+    assert!(bob.earned(tokens(3), U256::ZERO).is_err());
+}
+
+#[test]
+fn test_pending_rewards() {
+    let mut accumulator = RewardAccumulator::new(0);
+    accumulator
+        .notify_reward(tokens(100), 100, 0, tokens(4))
+        .unwrap();
+    let checkpoint = RewardCheckpoint::default();
+    assert_eq!(
+        checkpoint
+            .pending_rewards(&accumulator, tokens(1), 40, tokens(4))
+            .unwrap(),
+        tokens(10)
+    );
+    assert_eq!(accumulator.last_update, 0);
+}
+
+#[test]
+fn test_multi_token() {
+    let mut accumulators = MultiRewardAccumulator::new();
+    let mut user = UserRewards::new();
+    let staked = tokens(2);
+    user.checkpoint(&accumulators, staked).unwrap();
+    accumulators
+        .token_mut("uscrt".to_string(), 0)
+        .notify_reward(tokens(100), 100, 0, staked)
+        .unwrap();
+    accumulators
+        .token_mut("usilk".to_string(), 50)
+        .distribute(tokens(10), staked)
+        .unwrap();
+
+    let pending = user
+        .pending_rewards(&accumulators, staked, 50, staked)
+        .unwrap();
+    assert_eq!(
+        pending,
+        BTreeMap::from([
+            ("uscrt".to_string(), tokens(50)),
+            ("usilk".to_string(), tokens(10))
+        ])
+    );
+
+    accumulators.update(50, staked).unwrap();
+    user.checkpoint(&accumulators, staked).unwrap();
+    assert_eq!(user.claim(), pending);
+    assert_eq!(user.claim(), BTreeMap::new());
+
+    let bytes = accumulators.try_to_vec().unwrap();
+    assert_eq!(
+        MultiRewardAccumulator::try_from_slice(&bytes).unwrap(),
+        accumulators
+    );
+    let bytes = user.try_to_vec().unwrap();
+    assert_eq!(UserRewards::try_from_slice(&bytes).unwrap(), user);
+}
+
+#[test]
+fn test_multi_token_unchanged_on_err() {
+    let staked = tokens(1);
+    let mut accumulators = MultiRewardAccumulator::new();
+    accumulators
+        .token_mut("a".to_string(), 0)
+        .notify_reward(tokens(100), 100, 0, staked)
+        .unwrap();
+    // Streaming at U256::MAX per second overflows as soon as any time passes.
+    let b = accumulators.token_mut("b".to_string(), 0);
+    b.rate = U256::MAX;
+    b.period_finish = 100;
+    let before = accumulators.clone();
+    assert!(accumulators.update(10, staked).is_err());
+    assert_eq!(accumulators, before);
+
+    // Crediting "b" overflows after "a" has been credited.
+    accumulators.token_mut("a".to_string(), 0).reward_per_token = UNIT;
+    accumulators.token_mut("b".to_string(), 0).reward_per_token = U256::MAX;
+    let mut user = UserRewards::new();
+    assert!(user.checkpoint(&accumulators, U256::MAX).is_err());
+    assert_eq!(user, UserRewards::new());
+}
+
+proptest! {
+    #[test]
+    fn stakers_never_earn_more_than_distributed(
+        stakes in prop::collection::vec(1u64..u64::MAX, 1..8),
+        amount in 0u128..u128::MAX,
+    ) {
+        let stakes: Vec<U256> = stakes.into_iter().map(U256::from).collect();
+        let total = stakes.iter().fold(U256::ZERO, |total, stake| total + stake);
+        let mut accumulator = RewardAccumulator::new(0);
+        let rpt = accumulator.distribute(U256::new(amount), total).unwrap();
+        let earned = stakes.iter().fold(U256::ZERO, |earned, stake| {
+            earned + RewardCheckpoint::default().earned(*stake, rpt).unwrap()
+        });
+        prop_assert!(earned <= U256::new(amount));
+        // Each staker loses less than one unit to rounding down, plus the rounding of reward_per_token.
+        let dust = U256::from(stakes.len() as u64) + total / UNIT + 1;
+        prop_assert!(U256::new(amount) - earned <= dust);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::ring_buffer::RingBuffer;
+use borsh::{BorshDeserialize, BorshSerialize};
+use rstest::*;
+
+fn items(buffer: &RingBuffer<u64>) -> Vec<u64> {
+    buffer.iter().copied().collect()
+}
+
+#[test]
+fn test_push() {
+    let mut buffer = RingBuffer::new(3).unwrap();
+    assert!(buffer.is_empty());
+    assert_eq!((buffer.oldest(), buffer.latest()), (None, None));
+    assert!(buffer.latest_mut().is_none());
+    for x in 1..=5 {
+        buffer.push(x);
+    }
+    assert_eq!(items(&buffer), vec![3, 4, 5]);
+    assert_eq!((buffer.len(), buffer.capacity()), (3, 3));
+    assert_eq!((buffer.oldest(), buffer.latest()), (Some(&3), Some(&5)));
+
+    *buffer.latest_mut().unwrap() = 6;
+    assert_eq!(items(&buffer), vec![3, 4, 6]);
+    assert!(RingBuffer::<u64>::new(0).is_err());
+}
+
+#[test]
+fn test_serialization() {
+    let mut buffer = RingBuffer::new(2).unwrap();
+    for x in 1..=3u64 {
+        buffer.push(x);
+    }
+    let bytes = buffer.try_to_vec().unwrap();
+    assert_eq!(RingBuffer::<u64>::try_from_slice(&bytes).unwrap(), buffer);
+    let json = serde_json::to_string(&buffer).unwrap();
+    assert_eq!(json, r#"{"capacity":2,"items":[3,2],"head":1}"#);
+    assert_eq!(
+        serde_json::from_str::<RingBuffer<u64>>(&json).unwrap(),
+        buffer
+    );
+}
+
+#[rstest]
+#[case(r#"{"capacity":0,"items":[],"head":0}"#)]
+#[case(r#"{"capacity":1,"items":[1,2],"head":0}"#)]
+#[case(r#"{"capacity":2,"items":[1,2],"head":2}"#)]
+// The head only moves once the buffer is full.
+#[case(r#"{"capacity":3,"items":[1,2],"head":1}"#)]
+#[case(r#"{"capacity":2,"items":[1,2],"head":0,"tail":0}"#)]
+fn test_deserialize_invalid(#[case] json: &str) {
+    assert!(serde_json::from_str::<RingBuffer<u64>>(json).is_err());
+}
+
+#[test]
+fn test_borsh_deserialize_invalid() {
+    // Capacity 2, items [1, 2], head 2.
+    let bytes = (2u32, vec![1u64, 2], 2u32).try_to_vec().unwrap();
+    assert!(RingBuffer::<u64>::try_from_slice(&bytes).is_err());
+    let bytes = (2u32, vec![1u64, 2], 1u32).try_to_vec().unwrap();
+    assert_eq!(
+        RingBuffer::<u64>::try_from_slice(&bytes)
+            .unwrap()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>(),
+        vec![2, 1]
+    );
+}
@@ -0,0 +1,19 @@
+use crate::abi::schemas;
+use std::{fs, path::PathBuf};
+
+/// Compares each exported schema against its snapshot in the `schema` directory. Run `cargo schema` to update them.
+#[test]
+fn test_schema_snapshots() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schema");
+    for (name, schema) in schemas() {
+        let path = dir.join(format!("{}.json", name));
+        let snapshot = fs::read_to_string(&path)
+            .unwrap_or_else(|_| panic!("missing schema snapshot {}", path.display()));
+        let actual = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+        assert_eq!(
+            actual, snapshot,
+            "schema for {} is out of date, run `cargo schema`",
+            name
+        );
+    }
+}
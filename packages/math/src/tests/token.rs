@@ -0,0 +1,359 @@
+use crate::{
+    common::{bankers_round, exp10, Rounding},
+    traits::{
+        convert_amount, ConfidencePriceMath, ConvertedAmount, PriceMath, PriceSide, TokenMath,
+    },
+    U256,
+};
+use rstest::*;
+
+struct Truncating;
+
+impl TokenMath for Truncating {
+    const NORMALIZED_PRECISION: u8 = 18;
+    const BANKERS_ROUNDING_ENABLED: bool = false;
+
+    fn token_decimals(&self) -> u8 {
+        0
+    }
+}
+
+struct Bankers;
+
+impl TokenMath for Bankers {
+    const NORMALIZED_PRECISION: u8 = 18;
+    const BANKERS_ROUNDING_ENABLED: bool = true;
+
+    fn token_decimals(&self) -> u8 {
+        0
+    }
+}
+
+#[rstest]
+#[case(6, 1_234_567, 1_234_567_000_000_000_000)]
+#[case(18, 1_234_567, 1_234_567)]
+#[case(24, 1_234_567_000_000, 1_234_567)]
+#[case(27, 1_234_567_000_000_000, 1_234_567)]
+fn test_exact(#[case] decimals: u8, #[case] utokens: u128, #[case] normalized: u128) {
+    let (utokens, normalized) = (U256::new(utokens), U256::new(normalized));
+    assert_eq!(
+        Truncating::normalize_amount_from_any_utokens(utokens, decimals).unwrap(),
+        normalized
+    );
+    assert_eq!(
+        Truncating::denormalize_amount_to_any_utokens(normalized, decimals).unwrap(),
+        utokens
+    );
+    assert_eq!(
+        Bankers::normalize_amount_from_any_utokens(utokens, decimals).unwrap(),
+        normalized
+    );
+    assert_eq!(
+        Bankers::denormalize_amount_to_any_utokens(normalized, decimals).unwrap(),
+        utokens
+    );
+}
+
+#[rstest]
+// 1.5 and 2.5 normalized units, the first rounding to 2 and the second to 2 under bankers rounding.
+#[case(24, 1_500_000, 1, 2)]
+#[case(24, 2_500_000, 2, 2)]
+#[case(24, 2_600_000, 2, 3)]
+#[case(27, 1_500_000_000, 1, 2)]
+fn test_excess_decimals(
+    #[case] decimals: u8,
+    #[case] utokens: u128,
+    #[case] truncated: u128,
+    #[case] rounded: u128,
+) {
+    assert_eq!(
+        Truncating::normalize_amount_from_any_utokens(utokens, decimals).unwrap(),
+        truncated
+    );
+    assert_eq!(
+        Bankers::normalize_amount_from_any_utokens(utokens, decimals).unwrap(),
+        rounded
+    );
+}
+
+#[rstest]
+#[case(6, 1_500_000_000_000, 1, 2)]
+#[case(6, 2_500_000_000_000, 2, 2)]
+fn test_denormalize_rounding(
+    #[case] decimals: u8,
+    #[case] normalized: u128,
+    #[case] truncated: u128,
+    #[case] rounded: u128,
+) {
+    assert_eq!(
+        Truncating::denormalize_amount_to_any_utokens(normalized, decimals).unwrap(),
+        truncated
+    );
+    assert_eq!(
+        Bankers::denormalize_amount_to_any_utokens(normalized, decimals).unwrap(),
+        rounded
+    );
+}
+
+#[test]
+fn test_token_precision() {
+    let amount = U256::new(1_234_567_890_123_456_789);
+    assert_eq!(
+        Truncating::normalize_amount_to_any_token_precision(amount, 6).unwrap(),
+        1_234_567_000_000_000_000
+    );
+    assert_eq!(
+        Bankers::normalize_amount_to_any_token_precision(amount, 6).unwrap(),
+        1_234_568_000_000_000_000
+    );
+    assert_eq!(
+        Truncating::normalize_amount_to_any_token_precision(amount, 24).unwrap(),
+        amount
+    );
+    assert_eq!(
+        Bankers::normalize_amount_to_any_token_precision(amount, 27).unwrap(),
+        amount
+    );
+}
+
+#[test]
+fn test_errors() {
+    // Neither precision difference panics, both error.
+    assert!(Truncating::denormalize_amount_to_any_utokens(U256::ONE, 100).is_err());
+    assert!(Bankers::denormalize_amount_to_any_utokens(U256::ONE, 100).is_err());
+    assert!(Truncating::normalize_amount_from_any_utokens(U256::ONE, 100).is_err());
+    assert!(Truncating::normalize_amount_to_any_token_precision(U256::ONE, 100).is_err());
+    // Scaling up past U256::MAX errors instead of wrapping.
+    assert!(Truncating::normalize_amount_from_any_utokens(U256::MAX, 0).is_err());
+    assert!(Truncating::denormalize_amount_to_any_utokens(U256::MAX / exp10(8), 27).is_err());
+}
+
+#[rstest]
+// Only the first dropped digit decides, as in `common::bankers_round`, so 2.51 rounds to 2 rather than 3.
+#[case(251, 2)]
+#[case(250, 2)]
+#[case(350, 4)]
+#[case(249, 2)]
+#[case(260, 3)]
+fn test_bankers_first_dropped_digit(#[case] utokens: u128, #[case] normalized: u128) {
+    assert_eq!(
+        Bankers::normalize_amount_from_any_utokens(utokens, 20).unwrap(),
+        normalized
+    );
+    assert_eq!(bankers_round(U256::new(utokens), 2) / 100, normalized);
+}
+
+#[test]
+fn test_bankers_near_max() {
+    // U256::MAX ends in ...639935, so bankers rounding to 6 fewer decimals rounds up without overflowing.
+    let rounded = U256::MAX / exp10(6) + 1;
+    assert_eq!(
+        Bankers::normalize_amount_from_any_utokens(U256::MAX, 24).unwrap(),
+        rounded
+    );
+    assert_eq!(
+        Bankers::denormalize_amount_to_any_utokens(U256::MAX, 12).unwrap(),
+        rounded
+    );
+    // Scaling the rounded amount back up does not fit, so it errors.
+    assert!(Bankers::normalize_amount_to_any_token_precision(U256::MAX, 12).is_err());
+    assert_eq!(
+        Truncating::normalize_amount_from_any_utokens(U256::MAX, 24).unwrap(),
+        U256::MAX / exp10(6)
+    );
+}
+
+#[rstest]
+// 1 BTC at $60,000 into USDC.
+#[case(100_000_000, (8, 60_000), (6, 1), Rounding::Down, 60_000_000_000, 0)]
+// 1 wei at $3,000 is worth less than a micro USDC.
+#[case(1, (18, 3_000), (6, 1), Rounding::Down, 0, 1)]
+#[case(1, (18, 3_000), (6, 1), Rounding::Up, 1, 0)]
+#[case(1_234_567_890_123, (18, 1), (6, 1), Rounding::Down, 1, 234_567_890_123)]
+#[case(1_734_567_890_123, (18, 1), (6, 1), Rounding::HalfUp, 2, 0)]
+// The value lost to rounding is less than one source utoken.
+#[case(12_345, (6, 3), (6, 2), Rounding::Down, 18_517, 0)]
+#[case(5, (6, 1), (24, 2), Rounding::Down, 2_500_000_000_000_000_000, 0)]
+#[case(5, (6, 0), (24, 2), Rounding::Down, 0, 5)]
+fn test_convert_amount(
+    #[case] amount: u128,
+    #[case] from: (u8, u128),
+    #[case] to: (u8, u128),
+    #[case] rounding: Rounding,
+    #[case] converted: u128,
+    #[case] dust: u128,
+) {
+    let ((from_decimals, from_price), (to_decimals, to_price)) = (from, to);
+    // Prices with 18 decimals.
+    let price = |p: u128| U256::new(p) * exp10(18);
+    assert_eq!(
+        convert_amount(
+            amount,
+            from_decimals,
+            price(from_price),
+            to_decimals,
+            price(to_price),
+            rounding
+        )
+        .unwrap(),
+        ConvertedAmount {
+            amount: U256::new(converted),
+            dust: U256::new(dust),
+        }
+    );
+}
+
+#[test]
+fn test_convert_amount_errors() {
+    assert!(convert_amount(1u128, 6, 1u128, 6, 0u128, Rounding::Down).is_err());
+    assert!(convert_amount(1u128, 0, 1u128, 100, 1u128, Rounding::Down).is_err());
+    assert!(convert_amount(U256::MAX, 6, 2u128, 6, 1u128, Rounding::Down).is_err());
+}
+
+#[rstest]
+#[case(Rounding::Down, 1, 2)]
+#[case(Rounding::Up, 2, 3)]
+#[case(Rounding::HalfUp, 2, 3)]
+#[case(Rounding::HalfEven, 2, 2)]
+fn test_rounded(
+    #[case] rounding: Rounding,
+    #[case] one_and_half: u128,
+    #[case] two_and_half: u128,
+) {
+    // 1.5 and 2.5 normalized units as a 24 decimal token.
+    assert_eq!(
+        Bankers::normalize_amount_from_any_utokens_rounded(1_500_000u128, 24, rounding).unwrap(),
+        (U256::new(one_and_half), U256::new(500_000))
+    );
+    assert_eq!(
+        Truncating::normalize_amount_from_any_utokens_rounded(2_500_000u128, 24, rounding).unwrap(),
+        (U256::new(two_and_half), U256::new(500_000))
+    );
+    // 1.5 and 2.5 utokens of a 6 decimal token.
+    assert_eq!(
+        Bankers::denormalize_amount_to_any_utokens_rounded(1_500_000_000_000u128, 6, rounding)
+            .unwrap(),
+        (U256::new(one_and_half), U256::new(500_000_000_000))
+    );
+    assert_eq!(
+        Truncating::denormalize_amount_to_any_utokens_rounded(2_500_000_000_000u128, 6, rounding)
+            .unwrap(),
+        (U256::new(two_and_half), U256::new(500_000_000_000))
+    );
+    assert_eq!(
+        Truncating::normalize_amount_to_any_token_precision_rounded(
+            2_500_000_000_000u128,
+            6,
+            rounding
+        )
+        .unwrap(),
+        (
+            U256::new(two_and_half) * exp10(12),
+            U256::new(500_000_000_000)
+        )
+    );
+    // Scaling up is exact.
+    assert_eq!(
+        Truncating::normalize_amount_from_any_utokens_rounded(3u128, 6, rounding).unwrap(),
+        (exp10(12) * 3, U256::ZERO)
+    );
+    assert_eq!(
+        Truncating::denormalize_amount_to_any_utokens_rounded(3u128, 24, rounding).unwrap(),
+        (exp10(6) * 3, U256::ZERO)
+    );
+}
+
+#[test]
+fn test_rounded_by_token() {
+    let token = Truncating;
+    let amount = exp10(17) * 15;
+    assert_eq!(
+        token
+            .denormalize_amount_to_utokens_rounded(amount, Rounding::Up)
+            .unwrap(),
+        (U256::new(2), exp10(17) * 5)
+    );
+    assert_eq!(
+        token
+            .normalize_amount_to_token_precision_rounded(amount, Rounding::Down)
+            .unwrap(),
+        (exp10(18), exp10(17) * 5)
+    );
+    assert_eq!(
+        token
+            .normalize_amount_from_utokens_rounded(2u128, Rounding::Down)
+            .unwrap(),
+        (exp10(18) * 2, U256::ZERO)
+    );
+}
+
+struct Feed {
+    price: U256,
+    confidence: U256,
+}
+
+impl PriceMath for Feed {
+    const PRICE_PRECISION: u8 = 8;
+
+    fn price(&self) -> U256 {
+        self.price
+    }
+}
+
+impl ConfidencePriceMath for Feed {
+    fn confidence(&self) -> U256 {
+        self.confidence
+    }
+}
+
+#[test]
+fn test_conservative_value() {
+    // $10 +- $0.50
+    let feed = Feed {
+        price: U256::new(1_000_000_000),
+        confidence: U256::new(50_000_000),
+    };
+    assert_eq!(
+        feed.conservative_price(PriceSide::Collateral).unwrap(),
+        950_000_000
+    );
+    assert_eq!(
+        feed.conservative_price(PriceSide::Debt).unwrap(),
+        1_050_000_000
+    );
+    assert_eq!(
+        feed.calc_conservative_value_from_amount(3u128, PriceSide::Collateral)
+            .unwrap(),
+        28
+    );
+    assert_eq!(
+        feed.calc_conservative_value_from_amount(3u128, PriceSide::Debt)
+            .unwrap(),
+        32
+    );
+    assert_eq!(feed.calc_value_from_amount(3u128).unwrap(), 30);
+
+    let feed = Feed {
+        price: U256::new(1_000_000_000),
+        confidence: U256::new(2_000_000_000),
+    };
+    assert_eq!(feed.conservative_price(PriceSide::Collateral).unwrap(), 0);
+}
+
+#[rstest]
+#[case(1_000_000_000, 50_000_000, 5, true)]
+#[case(1_000_000_000, 50_000_001, 5, false)]
+#[case(1_000_000_000, 0, 0, true)]
+#[case(0, 0, 5, false)]
+fn test_check_confidence(
+    #[case] price: u128,
+    #[case] confidence: u128,
+    #[case] max_percent: u128,
+    #[case] ok: bool,
+) {
+    let feed = Feed {
+        price: U256::new(price),
+        confidence: U256::new(confidence),
+    };
+    assert_eq!(feed.check_confidence(exp10(16) * max_percent).is_ok(), ok);
+}
@@ -0,0 +1,130 @@
+use crate::{
+    twap::{TwapKind, TwapOracle},
+    ud60x18::UNIT,
+    U256,
+};
+use rstest::*;
+
+fn price(x: u128) -> U256 {
+    U256::new(x) * UNIT
+}
+
+fn assert_close(actual: U256, expected: U256) {
+    let diff = if actual > expected {
+        actual - expected
+    } else {
+        expected - actual
+    };
+    assert!(diff < U256::new(1_000_000), "{} != {}", actual, expected);
+}
+
+/// A price of 1 from time 0 and 3 from time 100.
+fn oracle(capacity: u32) -> TwapOracle {
+    let mut oracle = TwapOracle::new(price(1), 0, capacity).unwrap();
+    oracle.update(price(3), 100).unwrap();
+    oracle
+}
+
+#[rstest]
+#[case(200, 200, price(2))]
+#[case(200, 100, price(3))]
+#[case(100, 100, price(1))]
+#[case(150, 100, price(2))]
+// Starts between observations: (50 * 1 + 100 * 3) / 150.
+#[case(200, 150, U256::new(7) * UNIT / 3)]
+fn test_arithmetic(#[case] now: u64, #[case] window: u64, #[case] expected: U256) {
+    assert_eq!(
+        oracle(8)
+            .consult(now, window, TwapKind::Arithmetic)
+            .unwrap(),
+        expected
+    );
+}
+
+#[rstest]
+#[case(200, 100, price(3))]
+#[case(100, 100, price(1))]
+// sqrt(1 * 3)
+#[case(200, 200, U256::new(1_732_050_807_568_877_293))]
+fn test_geometric(#[case] now: u64, #[case] window: u64, #[case] expected: U256) {
+    assert_close(
+        oracle(8).consult(now, window, TwapKind::Geometric).unwrap(),
+        expected,
+    );
+}
+
+#[test]
+fn test_geometric_below_one() {
+    let mut oracle = TwapOracle::new(UNIT / 2, 0, 8).unwrap();
+    oracle.update(price(2), 60).unwrap();
+    assert_close(oracle.consult(120, 120, TwapKind::Geometric).unwrap(), UNIT);
+    assert_close(
+        oracle.consult(60, 60, TwapKind::Geometric).unwrap(),
+        UNIT / 2,
+    );
+    assert_eq!(
+        oracle.consult(120, 120, TwapKind::Arithmetic).unwrap(),
+        UNIT * 5 / 4
+    );
+}
+
+#[test]
+fn test_cumulative_wraps() {
+    let mut oracle = oracle(8);
+    oracle.accumulator.price_cumulative = U256::MAX - price(50);
+    oracle.update(price(5), 200).unwrap();
+    assert!(oracle.accumulator.price_cumulative < price(300));
+    assert_eq!(
+        oracle.consult(300, 100, TwapKind::Arithmetic).unwrap(),
+        price(5)
+    );
+}
+
+#[test]
+fn test_same_timestamp_replaces() {
+    let mut oracle = oracle(8);
+    oracle.update(price(5), 100).unwrap();
+    assert_eq!(oracle.iter().count(), 2);
+    assert_eq!(
+        oracle.consult(200, 100, TwapKind::Arithmetic).unwrap(),
+        price(5)
+    );
+}
+
+#[test]
+fn test_ring_buffer() {
+    let mut oracle = oracle(2);
+    oracle.update(price(5), 200).unwrap();
+    let timestamps: Vec<u64> = oracle.iter().map(|o| o.timestamp).collect();
+    assert_eq!(timestamps, vec![100, 200]);
+    assert_eq!(
+        oracle.consult(300, 200, TwapKind::Arithmetic).unwrap(),
+        price(4)
+    );
+    assert!(oracle.consult(300, 201, TwapKind::Arithmetic).is_err());
+}
+
+#[test]
+fn test_errors() {
+    assert!(TwapOracle::new(U256::ZERO, 0, 8).is_err());
+    assert!(TwapOracle::new(UNIT, 0, 0).is_err());
+    let mut oracle = oracle(8);
+    assert!(oracle.update(U256::ZERO, 200).is_err());
+    assert!(oracle.update(UNIT, 99).is_err());
+    assert!(oracle.consult(200, 0, TwapKind::Arithmetic).is_err());
+    assert!(oracle.consult(99, 10, TwapKind::Arithmetic).is_err());
+    assert!(oracle.consult(50, 100, TwapKind::Arithmetic).is_err());
+}
+
+#[test]
+fn test_deserialize_invalid() {
+    let json = serde_json::to_string(&oracle(2)).unwrap();
+    assert_eq!(
+        serde_json::from_str::<TwapOracle>(&json).unwrap(),
+        oracle(2)
+    );
+    assert_eq!(oracle(2).capacity(), 2);
+    // More observations than the capacity errors when loading instead of when updating.
+    let json = json.replace(r#""capacity":2"#, r#""capacity":1"#);
+    assert!(serde_json::from_str::<TwapOracle>(&json).is_err());
+}
@@ -0,0 +1,81 @@
+use super::*;
+use crate::common::Rounding;
+use crate::ud60x18::{format_decimal, parse_decimal, parse_decimal_rounded, MAX_UD60X18};
+
+#[rstest]
+#[case("0", "0")]
+#[case("-0", "0")]
+#[case("1", "1000000000000000000")]
+#[case("1.25", "1250000000000000000")]
+#[case("+1.25", "1250000000000000000")]
+#[case(".5", "500000000000000000")]
+#[case("7.", "7000000000000000000")]
+#[case("000123.4500", "123450000000000000000")]
+#[case("0.000000000000000001", "1")]
+#[case("1.25e3", "1250000000000000000000")]
+#[case("125E-2", "1250000000000000000")]
+#[case("1e-18", "1")]
+#[case("5e+2", "500000000000000000000")]
+#[case("0e100", "0")]
+#[case(
+    "115792089237316195423570985008687907853269984665640564039457.584007913129639935",
+    MAX_UD60X18
+)]
+fn test_parse_decimal(#[case] s: &str, #[case] expected: U256) {
+    assert_eq!(parse_decimal(s).unwrap(), expected);
+}
+
+#[rstest]
+#[case("")]
+#[case(".")]
+#[case("-")]
+#[case("1.2.3")]
+#[case("1,5")]
+#[case("e5")]
+#[case("1e")]
+#[case("1e+-2")]
+#[case(" 1")]
+#[case("-1")]
+#[case("-0.5e-17")]
+#[case("0.0000000000000000001")]
+#[case("1e-19")]
+#[case("1e78")]
+#[case("115792089237316195423570985008687907853269984665640564039457.584007913129639936")]
+fn test_parse_decimal_err(#[case] s: &str) {
+    assert!(parse_decimal(s).is_err());
+}
+
+#[rstest]
+#[case("1.0000000000000000014", Rounding::Down, "1000000000000000001")]
+#[case("1.0000000000000000014", Rounding::Up, "1000000000000000002")]
+#[case("1.0000000000000000015", Rounding::HalfUp, "1000000000000000002")]
+#[case("1.0000000000000000014", Rounding::HalfUp, "1000000000000000001")]
+#[case("1.0000000000000000015", Rounding::HalfEven, "1000000000000000002")]
+#[case("1.0000000000000000025", Rounding::HalfEven, "1000000000000000002")]
+#[case("1.00000000000000000250001", Rounding::HalfEven, "1000000000000000003")]
+#[case("4e-20", Rounding::Up, "1")]
+#[case("4e-20", Rounding::HalfUp, "0")]
+#[case("5e-19", Rounding::HalfUp, "1")]
+#[case("5e-19", Rounding::HalfEven, "0")]
+fn test_parse_decimal_rounded(#[case] s: &str, #[case] rounding: Rounding, #[case] expected: U256) {
+    assert_eq!(parse_decimal_rounded(s, rounding).unwrap(), expected);
+}
+
+#[rstest]
+#[case("0", 18, "0")]
+#[case("1", 18, "0.000000000000000001")]
+#[case("1", 17, "0")]
+#[case("1250000000000000000", 18, "1.25")]
+#[case("1259000000000000000", 2, "1.25")]
+#[case("2000000000000000000", 18, "2")]
+#[case("2000000000000000000", 0, "2")]
+#[case("1999999999999999999", 0, "1")]
+#[case("1999999999999999999", 255, "1.999999999999999999")]
+#[case(
+    MAX_UD60X18,
+    18,
+    "115792089237316195423570985008687907853269984665640564039457.584007913129639935"
+)]
+fn test_format_decimal(#[case] x: U256, #[case] max_decimals: u8, #[case] expected: &str) {
+    assert_eq!(format_decimal(x, max_decimals), expected);
+}
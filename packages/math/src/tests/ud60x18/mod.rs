@@ -3,6 +3,7 @@ pub(crate) use ethnum::U256;
 pub(crate) use rstest::*;
 
 mod avg;
+mod decimal;
 mod div;
 mod exp;
 mod exp2;
@@ -17,3 +18,4 @@ mod mul;
 mod pow;
 mod powu;
 mod sqrt;
+mod types;
@@ -0,0 +1,50 @@
+use super::*;
+use crate::ud60x18::{
+    from_decimal, from_decimal256, to_decimal, to_decimal256, UD60x18, MAX_UD60X18, UNIT,
+};
+use cosmwasm_std::{Decimal, Decimal256, Uint128, Uint256};
+
+#[rstest]
+#[case("0")]
+#[case("0.000000000000000001")]
+#[case("1.25")]
+#[case("340282366920938463463.374607431768211455")]
+fn test_decimal_roundtrip(#[case] s: &str) {
+    let decimal: Decimal = s.parse().unwrap();
+    let x = from_decimal(decimal);
+    assert_eq!(x, decimal.atomics().u128());
+    assert_eq!(to_decimal(x).unwrap(), decimal);
+    assert_eq!(Decimal::try_from(UD60x18::from(decimal)).unwrap(), decimal);
+}
+
+#[rstest]
+#[case("0")]
+#[case("1.25")]
+#[case("115792089237316195423570985008687907853269984665640564039457.584007913129639935")]
+fn test_decimal256_roundtrip(#[case] s: &str) {
+    let decimal: Decimal256 = s.parse().unwrap();
+    let x = from_decimal256(decimal);
+    assert_eq!(Uint256::from(x), decimal.atomics());
+    assert_eq!(to_decimal256(x), decimal);
+    assert_eq!(Decimal256::from(UD60x18::from(decimal)), decimal);
+    assert_eq!(UD60x18(x).to_string(), decimal.to_string());
+}
+
+#[test]
+fn test_decimal_overflow() {
+    let max = U256::new(u128::MAX);
+    assert_eq!(to_decimal(max).unwrap(), Decimal::new(Uint128::MAX));
+    assert!(to_decimal(max + 1).is_err());
+    assert!(Decimal::try_from(UD60x18(MAX_UD60X18)).is_err());
+}
+
+#[test]
+fn test_serde() {
+    let x = UD60x18(UNIT + exp10(17) * 2 + exp10(16) * 5);
+    let json = serde_json::to_string(&x).unwrap();
+    assert_eq!(json, "\"1.25\"");
+    assert_eq!(serde_json::from_str::<UD60x18>(&json).unwrap(), x);
+    assert!(serde_json::from_str::<UD60x18>("\"-1\"").is_err());
+    assert_eq!(UD60x18::ONE.to_string(), "1");
+    assert_eq!("0.5".parse::<UD60x18>().unwrap(), UD60x18(UNIT / 2));
+}
@@ -0,0 +1,175 @@
+use crate::{
+    traits::{Rebase, SimpleRebase, Vault, VaultAmounts, VirtualRebase},
+    ud60x18::UNIT,
+};
+use cosmwasm_std::Uint256;
+use ethnum::U256;
+use proptest::proptest;
+use rstest::*;
+
+/// A vault charging a 1% entry fee and a 1% exit fee.
+#[derive(Default)]
+struct FeeVault(SimpleRebase);
+
+impl Rebase for FeeVault {
+    fn elastic_uint256(&self) -> Uint256 {
+        self.0.elastic_uint256()
+    }
+
+    fn base_uint256(&self) -> Uint256 {
+        self.0.base_uint256()
+    }
+
+    fn elastic(&self) -> U256 {
+        self.0.elastic()
+    }
+
+    fn base(&self) -> U256 {
+        self.0.base()
+    }
+
+    fn set_elastic(&mut self, elastic: U256) {
+        self.0.set_elastic(elastic)
+    }
+
+    fn set_base(&mut self, base: U256) {
+        self.0.set_base(base)
+    }
+}
+
+impl Vault for FeeVault {
+    fn entry_fee(&self) -> U256 {
+        UNIT / 100
+    }
+
+    fn exit_fee(&self) -> U256 {
+        UNIT / 100
+    }
+}
+
+#[rstest]
+#[case(10, 6, 15, 7, 15)]
+#[case(1, 0, 2, 1, 1)]
+#[case(3, 2, 5, 2, 4)]
+fn test_preview_rounding(
+    #[case] amount: u128,
+    #[case] deposit_shares: u128,
+    #[case] mint_assets: u128,
+    #[case] withdraw_shares: u128,
+    #[case] redeem_assets: u128,
+) {
+    // 150 assets backing 100 shares.
+    let vault = SimpleRebase::new(U256::new(150), U256::new(100));
+    assert_eq!(vault.preview_deposit(amount).unwrap(), deposit_shares);
+    assert_eq!(vault.preview_mint(amount).unwrap(), mint_assets);
+    assert_eq!(vault.preview_withdraw(amount).unwrap(), withdraw_shares);
+    assert_eq!(vault.preview_redeem(amount).unwrap(), redeem_assets);
+    assert_eq!(vault.convert_to_shares(amount).unwrap(), deposit_shares);
+    assert_eq!(vault.convert_to_assets(amount).unwrap(), redeem_assets);
+}
+
+#[test]
+fn test_operations() {
+    let mut vault = SimpleRebase::default();
+    let amounts = vault.deposit(100u128).unwrap();
+    assert_eq!(
+        amounts,
+        VaultAmounts {
+            assets: U256::new(100),
+            shares: U256::new(100),
+            fee: U256::ZERO,
+        }
+    );
+    vault.set_elastic(U256::new(150));
+
+    let amounts = vault.mint(1u128).unwrap();
+    assert_eq!((amounts.assets, amounts.shares), (U256::new(2), U256::ONE));
+    assert_eq!((vault.elastic, vault.base), (U256::new(152), U256::new(101)));
+
+    let amounts = vault.withdraw(1u128).unwrap();
+    assert_eq!((amounts.assets, amounts.shares), (U256::ONE, U256::ONE));
+    assert_eq!((vault.elastic, vault.base), (U256::new(151), U256::new(100)));
+
+    let amounts = vault.redeem(100u128).unwrap();
+    assert_eq!(amounts.assets, 151);
+    assert_eq!((vault.elastic, vault.base), (U256::ZERO, U256::ZERO));
+
+    assert!(vault.redeem(1u128).is_err());
+}
+
+#[test]
+fn test_failed_redeem_unchanged() {
+    // 999 virtual-offset shares are worth 9990 assets, which the vault holds, but no real shares exist to burn.
+    let mut vault = VirtualRebase::new(U256::new(10_000), U256::ZERO, 3).unwrap();
+    assert_eq!(vault.preview_redeem(999u128).unwrap(), 9_990);
+    assert!(vault.redeem(999u128).is_err());
+    assert_eq!((vault.elastic, vault.base), (U256::new(10_000), U256::ZERO));
+    assert!(vault.withdraw(9_990u128).is_err());
+    assert_eq!((vault.elastic, vault.base), (U256::new(10_000), U256::ZERO));
+}
+
+#[test]
+fn test_fees() {
+    let mut vault = FeeVault(SimpleRebase::new(U256::new(1000), U256::new(1000)));
+
+    let amounts = vault.deposit(101u128).unwrap();
+    assert_eq!(
+        amounts,
+        VaultAmounts {
+            assets: U256::new(101),
+            shares: U256::new(100),
+            fee: U256::ONE,
+        }
+    );
+    // The fee leaves the vault.
+    assert_eq!((vault.elastic(), vault.base()), (U256::new(1100), U256::new(1100)));
+
+    let amounts = vault.mint(100u128).unwrap();
+    assert_eq!((amounts.assets, amounts.fee), (U256::new(101), U256::ONE));
+    assert_eq!(vault.preview_mint(100u128).unwrap(), 101);
+
+    let amounts = vault.withdraw(100u128).unwrap();
+    assert_eq!((amounts.shares, amounts.fee), (U256::new(101), U256::ONE));
+    assert_eq!(vault.preview_withdraw(100u128).unwrap(), 101);
+
+    let amounts = vault.redeem(101u128).unwrap();
+    assert_eq!((amounts.assets, amounts.fee), (U256::new(100), U256::ONE));
+    assert_eq!(vault.preview_redeem(101u128).unwrap(), 100);
+    assert_eq!((vault.elastic(), vault.base()), (U256::new(998), U256::new(998)));
+
+    // Fees round up, so even dust pays one unit.
+    assert_eq!(vault.deposit(1u128).unwrap().fee, 1);
+}
+
+#[test]
+fn test_max() {
+    let vault = SimpleRebase::new(U256::new(150), U256::new(100));
+    assert_eq!(vault.max_deposit(), U256::MAX - 150);
+    assert_eq!(vault.max_mint(), U256::MAX - 100);
+    assert_eq!(vault.max_withdraw(10u128).unwrap(), 15);
+    assert_eq!(vault.max_redeem(10u128), 10);
+}
+
+// Round trips through the vault must never give the user more than they put in.
+proptest! {
+    #[test]
+    fn proptest_round_trips(
+        elastic in 1..u64::MAX as u128,
+        base in 1..u64::MAX as u128,
+        amount in 0..u64::MAX as u128,
+        offset in 0..6u8,
+    ) {
+        let vault = VirtualRebase::new(U256::new(elastic), U256::new(base), offset).unwrap();
+        let shares = vault.preview_deposit(amount).unwrap();
+        assert!(vault.preview_redeem(shares).unwrap() <= amount);
+        assert!(vault.preview_mint(shares).unwrap() <= amount);
+
+        let assets = vault.preview_redeem(amount).unwrap();
+        assert!(vault.preview_withdraw(assets).unwrap() <= amount);
+        assert!(vault.preview_deposit(vault.preview_mint(amount).unwrap()).unwrap() >= amount);
+
+        let vault = FeeVault(SimpleRebase::new(U256::new(elastic), U256::new(base)));
+        let shares = vault.preview_deposit(amount).unwrap();
+        assert!(vault.preview_redeem(shares).unwrap() <= amount);
+    }
+}
@@ -0,0 +1,206 @@
+//! Exchange-rate history for a [Rebase], kept as a fixed-size ring buffer of checkpoints, e.g. to show the share price
+//! of a liquid staking derivative over time.
+
+use super::Rebase;
+use crate::{
+    common::{abs_diff, checked_add, checked_mul, muldiv, SECONDS_PER_YEAR},
+    ring_buffer::RingBuffer,
+    ud60x18::{self, UNIT},
+    I256, U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// The elastic and base of a [Rebase] at some point in time.
+#[borsh_serde]
+#[derive(Copy, Eq, Default)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct RebaseCheckpoint {
+    /// Time of the checkpoint in seconds.
+    pub timestamp: u64,
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub elastic: U256,
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub base: U256,
+}
+
+impl RebaseCheckpoint {
+    pub fn new(timestamp: u64, rebase: &(impl Rebase + ?Sized)) -> Self {
+        Self {
+            timestamp,
+            elastic: rebase.elastic(),
+            base: rebase.base(),
+        }
+    }
+
+    /// Elastic per base as an unsigned 60.18-decimal number, rounded down. One when there is no base.
+    pub fn exchange_rate(&self) -> StdResult<U256> {
+        if self.base == 0 {
+            return Ok(UNIT);
+        }
+        muldiv(self.elastic, UNIT, self.base)
+    }
+
+    /// Simple annualized growth of the exchange rate from `self` to `later`, as a signed 59.18-decimal number.
+    pub fn apr(&self, later: &RebaseCheckpoint) -> StdResult<I256> {
+        let (growth, elapsed) = self.growth(later)?;
+        let apr = muldiv(
+            abs_diff(growth, UNIT),
+            U256::from(SECONDS_PER_YEAR),
+            elapsed,
+        )?;
+        signed(apr, growth < UNIT)
+    }
+
+    /// Compounded annualized growth of the exchange rate from `self` to `later`, as a signed 59.18-decimal number.
+    pub fn apy(&self, later: &RebaseCheckpoint) -> StdResult<I256> {
+        let (growth, elapsed) = self.growth(later)?;
+        let periods = muldiv(U256::from(SECONDS_PER_YEAR), UNIT, elapsed)?;
+        let compounded = ud60x18::pow(growth, periods)?;
+        signed(abs_diff(compounded, UNIT), compounded < UNIT)
+    }
+
+    /// The ratio of the exchange rates of `later` and `self`, and the seconds between them.
+    fn growth(&self, later: &RebaseCheckpoint) -> StdResult<(U256, U256)> {
+        if later.timestamp <= self.timestamp {
+            return Err(StdError::generic_err(format!(
+                "Checkpoint at {} must be after checkpoint at {}",
+                later.timestamp, self.timestamp
+            )));
+        }
+        let growth = ud60x18::div(later.exchange_rate()?, self.exchange_rate()?)?;
+        Ok((growth, U256::from(later.timestamp - self.timestamp)))
+    }
+}
+
+/// The most recent [RebaseCheckpoint]s, oldest first.
+#[borsh_serde]
+#[derive(Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct RebaseHistory {
+    /// Recording past the capacity overwrites the oldest checkpoint.
+    checkpoints: RingBuffer<RebaseCheckpoint>,
+}
+
+impl RebaseHistory {
+    pub fn new(capacity: u32) -> StdResult<Self> {
+        Ok(Self {
+            checkpoints: RingBuffer::new(capacity)?,
+        })
+    }
+
+    /// Maximum number of checkpoints kept.
+    pub fn capacity(&self) -> u32 {
+        self.checkpoints.capacity()
+    }
+
+    pub fn len(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    /// Iterates over the checkpoints from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &RebaseCheckpoint> {
+        self.checkpoints.iter()
+    }
+
+    pub fn oldest(&self) -> Option<&RebaseCheckpoint> {
+        self.checkpoints.oldest()
+    }
+
+    pub fn latest(&self) -> Option<&RebaseCheckpoint> {
+        self.checkpoints.latest()
+    }
+
+    /// Records the state of `rebase` at `timestamp`. A checkpoint at the same time as the latest one replaces it.
+    pub fn record(&mut self, timestamp: u64, rebase: &(impl Rebase + ?Sized)) -> StdResult<()> {
+        let checkpoint = RebaseCheckpoint::new(timestamp, rebase);
+        match self.checkpoints.latest_mut() {
+            Some(latest) if timestamp < latest.timestamp => {
+                return Err(StdError::generic_err(format!(
+                    "Checkpoint at {} is older than the latest checkpoint at {}",
+                    timestamp, latest.timestamp
+                )))
+            }
+            Some(latest) if timestamp == latest.timestamp => *latest = checkpoint,
+            _ => self.checkpoints.push(checkpoint),
+        }
+        Ok(())
+    }
+
+    /// The newest checkpoint at or before `timestamp`.
+    pub fn checkpoint_at(&self, timestamp: u64) -> Option<&RebaseCheckpoint> {
+        self.iter().rev().find(|c| c.timestamp <= timestamp)
+    }
+
+    /// Annualized growth between the checkpoints in effect at `from` and `to`. See [RebaseCheckpoint::apr].
+    pub fn apr(&self, from: u64, to: u64) -> StdResult<I256> {
+        let (from, to) = self.checkpoints_between(from, to)?;
+        from.apr(to)
+    }
+
+    /// Compounded annualized growth between the checkpoints in effect at `from` and `to`. See [RebaseCheckpoint::apy].
+    pub fn apy(&self, from: u64, to: u64) -> StdResult<I256> {
+        let (from, to) = self.checkpoints_between(from, to)?;
+        from.apy(to)
+    }
+
+    /// Time-weighted average exchange rate from `from` to `to`, as an unsigned 60.18-decimal number.
+    ///
+    /// Each checkpoint's exchange rate is assumed to hold until the next checkpoint, and the latest one until `to`.
+    pub fn twap(&self, from: u64, to: u64) -> StdResult<U256> {
+        if to <= from {
+            return Err(StdError::generic_err(format!(
+                "TWAP end {} must be after start {}",
+                to, from
+            )));
+        }
+        match self.oldest() {
+            Some(oldest) if oldest.timestamp <= from => {}
+            _ => {
+                return Err(StdError::not_found(format!(
+                    "Rebase checkpoint at {}",
+                    from
+                )))
+            }
+        }
+
+        let mut weighted = U256::ZERO;
+        let ends = self
+            .iter()
+            .skip(1)
+            .map(|c| c.timestamp)
+            .chain(Some(u64::MAX));
+        for (checkpoint, end) in self.iter().zip(ends) {
+            let start = checkpoint.timestamp.max(from);
+            let end = end.min(to);
+            if start < end {
+                let rate = checkpoint.exchange_rate()?;
+                weighted = checked_add(weighted, checked_mul(rate, U256::from(end - start))?)?;
+            }
+        }
+        Ok(weighted / U256::from(to - from))
+    }
+
+    fn checkpoints_between(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> StdResult<(&RebaseCheckpoint, &RebaseCheckpoint)> {
+        let at = |timestamp| {
+            self.checkpoint_at(timestamp)
+                .ok_or_else(|| StdError::not_found(format!("Rebase checkpoint at {}", timestamp)))
+        };
+        Ok((at(from)?, at(to)?))
+    }
+}
+
+/// Gets `magnitude` as an [I256], negated if `negative`.
+fn signed(magnitude: U256, negative: bool) -> StdResult<I256> {
+    let value = I256::try_from(magnitude)
+        .map_err(|_| StdError::generic_err(format!("{} does not fit in I256", magnitude)))?;
+    Ok(if negative { -value } else { value })
+}
@@ -0,0 +1,120 @@
+//! A collection of [SimpleRebase] buckets, such as one pool per validator or per epoch for a staking derivative.
+
+use super::{Rebase, SimpleRebase};
+use crate::{
+    common::{checked_add, Rounding},
+    ud60x18::UNIT,
+    U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+use std::{collections::BTreeMap, fmt::Debug};
+
+#[borsh_serde]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct RebaseLedger<K: Ord> {
+    pub buckets: BTreeMap<K, SimpleRebase>,
+}
+
+impl<K: Ord> Default for RebaseLedger<K> {
+    fn default() -> Self {
+        Self {
+            buckets: BTreeMap::new(),
+        }
+    }
+}
+
+/// The result of moving base between two buckets of a [RebaseLedger].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerMove {
+    /// Base burned from the source bucket.
+    pub base_out: U256,
+    /// Elastic moved between the buckets.
+    pub elastic: U256,
+    /// Base minted in the destination bucket.
+    pub base_in: U256,
+}
+
+impl<K: Ord + Clone + Debug> RebaseLedger<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&SimpleRebase> {
+        self.buckets.get(key)
+    }
+
+    /// Gets the bucket for `key`, creating an empty one if it does not exist.
+    pub fn bucket_mut(&mut self, key: K) -> &mut SimpleRebase {
+        self.buckets.entry(key).or_default()
+    }
+
+    /// Removes the bucket for `key`.
+    pub fn remove(&mut self, key: &K) -> Option<SimpleRebase> {
+        self.buckets.remove(key)
+    }
+
+    /// Burns `base` from the `from` bucket and mints base for the elastic it was worth in the `to` bucket.
+    ///
+    /// Both conversions round down so that neither bucket loses value. Nothing changes if either step fails.
+    pub fn move_base(
+        &mut self,
+        from: &K,
+        to: K,
+        base: impl Into<U256> + Copy,
+    ) -> StdResult<LedgerMove> {
+        if *from == to {
+            return Err(StdError::generic_err(format!(
+                "Cannot move base from bucket {:?} to itself",
+                from
+            )));
+        }
+        let mut source = self.get(from).cloned().ok_or_else(|| missing(from))?;
+        let mut destination = self.get(&to).cloned().unwrap_or_default();
+
+        let (_, elastic) = source.sub_base(base, Rounding::Down)?;
+        let (_, base_in) = destination.add_elastic(elastic, Rounding::Down)?;
+
+        self.buckets.insert(from.clone(), source);
+        self.buckets.insert(to, destination);
+        Ok(LedgerMove {
+            base_out: base.into(),
+            elastic,
+            base_in,
+        })
+    }
+
+    /// Sum of the elastic in every bucket.
+    pub fn total_elastic(&self) -> StdResult<U256> {
+        self.buckets.values().try_fold(U256::ZERO, |total, bucket| {
+            checked_add(total, bucket.elastic)
+        })
+    }
+
+    /// Sum of the base in every bucket. Base is only comparable within a bucket, so this is mostly useful for
+    /// bookkeeping.
+    pub fn total_base(&self) -> StdResult<U256> {
+        self.buckets
+            .values()
+            .try_fold(U256::ZERO, |total, bucket| checked_add(total, bucket.base))
+    }
+
+    /// Elastic per base of the bucket for `key` as an unsigned 60.18-decimal number, rounded down.
+    pub fn exchange_rate(&self, key: &K) -> StdResult<U256> {
+        self.get(key)
+            .ok_or_else(|| missing(key))?
+            .to_elastic(UNIT, Rounding::Down)
+    }
+
+    /// Snapshot of the exchange rate of every bucket.
+    pub fn exchange_rates(&self) -> StdResult<BTreeMap<K, U256>> {
+        self.buckets
+            .iter()
+            .map(|(key, bucket)| Ok((key.clone(), bucket.to_elastic(UNIT, Rounding::Down)?)))
+            .collect()
+    }
+}
+
+fn missing(key: &impl Debug) -> StdError {
+    StdError::not_found(format!("RebaseLedger bucket {:?}", key))
+}
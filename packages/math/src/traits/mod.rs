@@ -1,5 +1,15 @@
+mod history;
+mod ledger;
 mod rebase;
 mod token;
+mod vault;
 
+pub use btr_macros::Rebase;
+/// The `Uint256` of [Rebase]'s signatures, re-exported so `#[derive(Rebase)]` works whatever name cosmwasm is
+/// imported under.
+pub use cosmwasm_std::Uint256;
+pub use history::*;
+pub use ledger::*;
 pub use rebase::*;
-pub use token::*;
\ No newline at end of file
+pub use token::*;
+pub use vault::*;
\ No newline at end of file
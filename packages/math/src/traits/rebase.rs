@@ -1,6 +1,6 @@
-use crate::{U256, common::{muldiv, checked_add, checked_sub}};
+use crate::{U256, common::{exp10, muldiv, muldiv_rounded, checked_add, checked_sub, Rounding}, ud60x18::UNIT};
 use btr_macros::borsh_serde;
-use cosmwasm_std::{Uint256, StdResult};
+use cosmwasm_std::{Uint256, StdError, StdResult};
 
 pub trait Rebase {
     fn elastic_uint256(&self) -> Uint256;
@@ -12,75 +12,161 @@ pub trait Rebase {
     fn into_rebase(&self) -> SimpleRebase {
         SimpleRebase::new(self.elastic(), self.base())
     }
-    /// Calculates the base value in relationship to `elastic` and self
-    fn to_base(&self, elastic: impl Into<U256> + Copy, round_up: bool) -> StdResult<U256> {
+
+    /// Virtual elastic added to `self.elastic()` when converting between elastic and base. Zero unless overridden.
+    fn virtual_elastic(&self) -> StdResult<U256> {
+        Ok(U256::ZERO)
+    }
+
+    /// Virtual base added to `self.base()` when converting between elastic and base. Zero unless overridden.
+    fn virtual_base(&self) -> StdResult<U256> {
+        Ok(U256::ZERO)
+    }
+
+    /// The elastic and base totals used for conversions, including any virtual offsets.
+    fn conversion_totals(&self) -> StdResult<(U256, U256)> {
+        Ok((
+            checked_add(self.elastic(), self.virtual_elastic()?)?,
+            checked_add(self.base(), self.virtual_base()?)?,
+        ))
+    }
+
+    /// Calculates the base value in relationship to `elastic` and self, rounded with the [Rounding] `rounding`. A `bool`
+    /// converts through `From<bool>`, so `true` rounds up and `false` rounds down.
+    fn to_base(&self, elastic: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<U256> {
         let elastic = elastic.into();
-        let mut base: U256;
-        if self.elastic() == 0 {
-            base = elastic;
-        } else {
-            base = muldiv(elastic, self.base(), self.elastic())?;
-            if round_up && muldiv(base, self.elastic(), self.base())? < elastic {
-                base += U256::ONE;
-            }
+        let (total_elastic, total_base) = self.conversion_totals()?;
+        if total_elastic == 0 {
+            return Ok(elastic);
+        }
+        muldiv_rounded(elastic, total_base, total_elastic, rounding.into())
+    }
+
+    /// Like [Rebase::to_base], but errors when a nonzero `elastic` is worth zero base, e.g. a dust deposit
+    fn to_base_checked(&self, elastic: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<U256> {
+        let base = self.to_base(elastic, rounding)?;
+        let elastic: U256 = elastic.into();
+        if base == 0 && elastic != 0 {
+            return Err(StdError::generic_err(format!("Elastic {} is worth zero base", elastic)));
         }
         Ok(base)
     }
 
-    /// Calculates the elastic value in relationship to `base` and self
-    fn to_elastic(&self, base: impl Into<U256> + Copy, round_up: bool) -> StdResult<U256> {
+    /// Calculates the elastic value in relationship to `base` and self, rounded with the [Rounding] `rounding`. A `bool`
+    /// converts through `From<bool>`, so `true` rounds up and `false` rounds down.
+    fn to_elastic(&self, base: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<U256> {
         let base = base.into();
-        let mut elastic: U256;
-        if self.base() == 0 {
-            elastic = base;
-        } else {
-            elastic = muldiv(base, self.elastic(), self.base())?;
-            if round_up && muldiv(elastic, self.base(), self.elastic())? < base {
-                elastic += U256::ONE;
-            }
+        let (total_elastic, total_base) = self.conversion_totals()?;
+        if total_base == 0 {
+            return Ok(base);
         }
-        Ok(elastic)
+        muldiv_rounded(base, total_elastic, total_base, rounding.into())
     }
 
     /// Add `elastic` to `self` and update `total.base`
-    fn add_elastic(&mut self, elastic: impl Into<U256> + Copy, round_up: bool) -> StdResult<(&mut Self, U256)> {
-        let base = self.to_base(elastic, round_up)?;
+    fn add_elastic(&mut self, elastic: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<(&mut Self, U256)> {
+        let base = self.to_base(elastic, rounding)?;
         let elastic: U256 = elastic.into();
-        self.set_elastic(checked_add(self.elastic(), elastic)?);
-        self.set_base(checked_add(self.base(), base)?);
+        let new_elastic = checked_add(self.elastic(), elastic)?;
+        let new_base = checked_add(self.base(), base)?;
+        self.set_elastic(new_elastic);
+        self.set_base(new_base);
+        Ok((self, base))
+    }
+
+    /// Like [Rebase::add_elastic], but errors instead of adding a nonzero `elastic` that is worth zero base
+    fn add_elastic_checked(&mut self, elastic: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<(&mut Self, U256)> {
+        let base = self.to_base_checked(elastic, rounding)?;
+        let elastic: U256 = elastic.into();
+        let new_elastic = checked_add(self.elastic(), elastic)?;
+        let new_base = checked_add(self.base(), base)?;
+        self.set_elastic(new_elastic);
+        self.set_base(new_base);
         Ok((self, base))
     }
 
     /// Sub `elastic` from `self` and update `total.base`
-    fn sub_elastic(&mut self, elastic: impl Into<U256> + Copy, round_up: bool) -> StdResult<(&mut Self, U256)> {
-        let base = self.to_base(elastic, round_up)?;
+    fn sub_elastic(&mut self, elastic: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<(&mut Self, U256)> {
+        let base = self.to_base(elastic, rounding)?;
         let elastic: U256 = elastic.into();
-        self.set_elastic(checked_sub(self.elastic(), elastic)?);
-        // The amount we are subtracting from elastic and base are proportional in this function
-        // so if we pass the checked_sub above, we don't need to check again.
-        self.set_base(self.base() - base);
+        let new_elastic = checked_sub(self.elastic(), elastic)?;
+        // The amounts are proportional, but virtual offsets can still make base exceed the real total, so both totals
+        // are computed before either is written.
+        let new_base = checked_sub(self.base(), base)?;
+        self.set_elastic(new_elastic);
+        self.set_base(new_base);
         Ok((self, base))
     }
 
     /// Add `base` to `total` and update `self.elastic()`
-    fn add_base(&mut self, base: impl Into<U256> + Copy, round_up: bool) -> StdResult<(&mut Self, U256)> {
-        let elastic = self.to_elastic(base, round_up)?;
-        self.set_elastic(checked_add(self.elastic(), elastic)?);
+    fn add_base(&mut self, base: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<(&mut Self, U256)> {
+        let elastic = self.to_elastic(base, rounding)?;
+        let new_elastic = checked_add(self.elastic(), elastic)?;
         let base: U256 = base.into();
-        self.set_base(checked_add(self.base(), base)?);
+        let new_base = checked_add(self.base(), base)?;
+        self.set_elastic(new_elastic);
+        self.set_base(new_base);
         Ok((self, elastic))
     }
 
     /// Sub `base` from `total` and update `self.elastic()`
-    fn sub_base(&mut self, base: impl Into<U256> + Copy, round_up: bool) -> StdResult<(&mut Self, U256)> {
-        let elastic = self.to_elastic(base, round_up)?;
-        self.set_elastic(checked_sub(self.elastic(), elastic)?);
-        // The amount we are subtracting from elastic and base are proportional in this function
-        // so if we pass the checked_sub above, we don't need to check again.
+    fn sub_base(&mut self, base: impl Into<U256> + Copy, rounding: impl Into<Rounding>) -> StdResult<(&mut Self, U256)> {
+        let elastic = self.to_elastic(base, rounding)?;
+        let new_elastic = checked_sub(self.elastic(), elastic)?;
+        // The amounts are proportional, but virtual offsets can still make base exceed the real total, so both totals
+        // are computed before either is written.
         let base: U256 = base.into();
-        self.set_base(self.base() - base);
+        let new_base = checked_sub(self.base(), base)?;
+        self.set_elastic(new_elastic);
+        self.set_base(new_base);
         Ok((self, elastic))
     }
+
+    /// Add `elastic` to `self` without minting base, e.g. when yield accrues, raising the elastic value of every base
+    fn accrue(&mut self, elastic: impl Into<U256> + Copy) -> StdResult<&mut Self> {
+        let elastic: U256 = elastic.into();
+        self.set_elastic(checked_add(self.elastic(), elastic)?);
+        Ok(self)
+    }
+
+    /// Accrue `yield_elastic` and mint base worth a `fee_rate` share of it (an unsigned 60.18-decimal fraction) to a
+    /// fee recipient, returning the minted base.
+    ///
+    /// The minted base is rounded down, so the fee recipient never receives more than `fee_rate` of the yield and
+    /// existing base never loses value.
+    fn accrue_with_fee(&mut self, yield_elastic: impl Into<U256> + Copy, fee_rate: impl Into<U256> + Copy) -> StdResult<(&mut Self, U256)> {
+        let yield_elastic: U256 = yield_elastic.into();
+        let fee_rate: U256 = fee_rate.into();
+        if fee_rate > UNIT {
+            return Err(StdError::generic_err(format!("Fee rate {} must be <= {}", fee_rate, UNIT)));
+        }
+        // Everything is computed from the post-accrual totals before writing, so an error leaves self unchanged.
+        let elastic = checked_add(self.elastic(), yield_elastic)?;
+        let (virtual_elastic, virtual_base) = (self.virtual_elastic()?, self.virtual_base()?);
+        let total_elastic = checked_add(elastic, virtual_elastic)?;
+        let total_base = checked_add(self.base(), virtual_base)?;
+        let fee_elastic = muldiv(yield_elastic, fee_rate, UNIT)?;
+        // Solves fee_base / (total_base + fee_base) * total_elastic = fee_elastic.
+        let fee_base = if total_base == 0 {
+            fee_elastic
+        } else if fee_elastic == total_elastic {
+            return Err(StdError::generic_err("Fee cannot take all elastic from existing base"));
+        } else {
+            muldiv(fee_elastic, total_base, total_elastic - fee_elastic)?
+        };
+        let base = checked_add(self.base(), fee_base)?;
+        self.set_elastic(elastic);
+        self.set_base(base);
+        Ok((self, fee_base))
+    }
+
+    /// Sub `elastic` from `self` without burning base, e.g. when a loss is realized, lowering the elastic value of
+    /// every base
+    fn slash(&mut self, elastic: impl Into<U256> + Copy) -> StdResult<&mut Self> {
+        let elastic: U256 = elastic.into();
+        self.set_elastic(checked_sub(self.elastic(), elastic)?);
+        Ok(self)
+    }
 }
 
 #[borsh_serde]
@@ -96,6 +182,25 @@ impl SimpleRebase {
     }
 }
 
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for SimpleRebase {
+    fn schema_name() -> String {
+        "SimpleRebase".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        // U256 serializes as a string, so describe the fields through the string wrapper.
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        #[serde(deny_unknown_fields)]
+        struct SimpleRebase {
+            elastic: crate::U256Str,
+            base: crate::U256Str,
+        }
+        SimpleRebase::json_schema(gen)
+    }
+}
+
 impl Rebase for SimpleRebase {
     fn elastic_uint256(&self) -> Uint256 {
         self.elastic.into()
@@ -121,3 +226,92 @@ impl Rebase for SimpleRebase {
         self.base = base;
     }
 }
+
+/// A [Rebase] with ERC-4626 style virtual shares to protect share vaults from the first depositor (donation) attack.
+///
+/// Conversions behave as if there were an extra `10^decimals_offset` base backed by an extra 1 elastic. An attacker
+/// who donates elastic to an empty rebase then mostly donates it to those virtual shares, and the offset makes
+/// rounding a victim's deposit down to zero base require a donation `10^decimals_offset` times larger than the deposit.
+/// See https://docs.openzeppelin.com/contracts/4.x/erc4626#inflation-attack.
+#[borsh_serde]
+#[derive(Default)]
+pub struct VirtualRebase {
+    pub elastic: U256,
+    pub base: U256,
+    /// Base precision added on top of the elastic precision. Must be at most 77, or conversions error.
+    pub decimals_offset: u8,
+}
+
+impl VirtualRebase {
+    pub const MAX_DECIMALS_OFFSET: u8 = 77;
+
+    pub fn new(elastic: U256, base: U256, decimals_offset: u8) -> StdResult<Self> {
+        check_decimals_offset(decimals_offset)?;
+        Ok(Self { elastic, base, decimals_offset })
+    }
+}
+
+/// Errors if 10^`decimals_offset` does not fit in a U256. The offset is a public, deserializable field, so it is
+/// checked on every conversion rather than trusted.
+fn check_decimals_offset(decimals_offset: u8) -> StdResult<()> {
+    if decimals_offset > VirtualRebase::MAX_DECIMALS_OFFSET {
+        return Err(StdError::generic_err(format!("Decimals offset {} must be <= {}", decimals_offset, VirtualRebase::MAX_DECIMALS_OFFSET)));
+    }
+    Ok(())
+}
+
+impl Rebase for VirtualRebase {
+    fn elastic_uint256(&self) -> Uint256 {
+        self.elastic.into()
+    }
+
+    fn base_uint256(&self) -> Uint256 {
+        self.base.into()
+    }
+
+    fn elastic(&self) -> U256 {
+        self.elastic
+    }
+
+    fn base(&self) -> U256 {
+        self.base
+    }
+
+    fn set_elastic(&mut self, elastic: U256) {
+        self.elastic = elastic;
+    }
+
+    fn set_base(&mut self, base: U256) {
+        self.base = base;
+    }
+
+    fn virtual_elastic(&self) -> StdResult<U256> {
+        Ok(U256::ONE)
+    }
+
+    fn virtual_base(&self) -> StdResult<U256> {
+        check_decimals_offset(self.decimals_offset)?;
+        Ok(exp10(self.decimals_offset))
+    }
+}
+
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for VirtualRebase {
+    fn schema_name() -> String {
+        "VirtualRebase".to_string()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        // U256 serializes as a string, so describe the fields through the string wrapper.
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema)]
+        #[serde(deny_unknown_fields)]
+        struct VirtualRebase {
+            elastic: crate::U256Str,
+            base: crate::U256Str,
+            /// Base precision added on top of the elastic precision. Must be at most 77, or conversions error.
+            decimals_offset: u8,
+        }
+        VirtualRebase::json_schema(gen)
+    }
+}
@@ -1,55 +1,84 @@
 use cosmwasm_std::{StdResult, StdError};
 use ethnum::U256;
 
-use crate::common::{exp10, muldiv, bankers_round};
+use btr_macros::borsh_serde;
+use crate::{common::{exp10, muldiv, muldiv_rounded, muldiv_up, nth_digit, checked_add, checked_mul, Rounding}, ud60x18::UNIT};
 
 pub trait TokenMath {
     const NORMALIZED_PRECISION: u8;
     const BANKERS_ROUNDING_ENABLED: bool;
     /// Amount (token decimal precision) -> Amount (normalized decimal precision).
+    ///
+    /// Tokens with more decimals than the normalized precision lose their excess precision, which is truncated or rounded.
     fn normalize_amount_from_any_utokens(amount: impl Into<U256>, token_decimals: u8) -> StdResult<U256> {
         let amount: U256 = amount.into();
-        if token_decimals == Self::NORMALIZED_PRECISION {
-            Ok(amount)
+        let precision_diff = decimals_diff(token_decimals, Self::NORMALIZED_PRECISION)?;
+        if token_decimals <= Self::NORMALIZED_PRECISION {
+            checked_mul(amount, exp10(precision_diff))
         } else {
-            muldiv(amount, exp10(Self::NORMALIZED_PRECISION.into()), exp10(token_decimals))
+            scale_down(amount, precision_diff, Self::BANKERS_ROUNDING_ENABLED)
         }
     }
 
     /// Amount (normalized decimal precision) -> Amount (token decimal precision).
     fn denormalize_amount_to_any_utokens(amount: impl Into<U256>, token_decimals: u8) -> StdResult<U256> {
         let normalized_amount: U256 = amount.into();
-        if token_decimals == Self::NORMALIZED_PRECISION {
-            Ok(normalized_amount)
+        let precision_diff = decimals_diff(token_decimals, Self::NORMALIZED_PRECISION)?;
+        if token_decimals <= Self::NORMALIZED_PRECISION {
+            scale_down(normalized_amount, precision_diff, Self::BANKERS_ROUNDING_ENABLED)
         } else {
-            if Self::BANKERS_ROUNDING_ENABLED {
-                if token_decimals > Self::NORMALIZED_PRECISION {
-                    return Err(StdError::generic_err(format!("Token decimals {} must be <= normalized precision {}", token_decimals, Self::NORMALIZED_PRECISION)));
-                };
-                let precision_diff = Self::NORMALIZED_PRECISION - token_decimals;
-                Ok(bankers_round(normalized_amount.into(), precision_diff) / exp10(precision_diff))
-            } else {
-                Ok(normalized_amount / exp10(Self::NORMALIZED_PRECISION - token_decimals))
-            }
+            checked_mul(normalized_amount, exp10(precision_diff))
         }
     }
 
     /// Amount (normalized decimal precision) -> Amount (normalized decimals, but excess precision truncated or rounded)
+    ///
+    /// Tokens with at least the normalized precision have no excess precision, so the amount is returned as is.
     fn normalize_amount_to_any_token_precision(amount: impl Into<U256>, token_decimals: u8) -> StdResult<U256> {
         let amount: U256 = amount.into();
-        if token_decimals == Self::NORMALIZED_PRECISION {
+        let precision_diff = decimals_diff(token_decimals, Self::NORMALIZED_PRECISION)?;
+        if token_decimals >= Self::NORMALIZED_PRECISION {
             Ok(amount)
         } else {
-            if token_decimals > Self::NORMALIZED_PRECISION {
-                return Err(StdError::generic_err(format!("Token decimals {} must be <= normalized precision {}", token_decimals, Self::NORMALIZED_PRECISION)));
-            };
-            let precision_diff = Self::NORMALIZED_PRECISION - token_decimals;
-            if Self::BANKERS_ROUNDING_ENABLED {
-                Ok(bankers_round(amount, precision_diff))
-            } else {
-                let truncated = amount / exp10(precision_diff);
-                Ok(truncated * exp10(precision_diff))
-            }
+            let scaled = scale_down(amount, precision_diff, Self::BANKERS_ROUNDING_ENABLED)?;
+            checked_mul(scaled, exp10(precision_diff))
+        }
+    }
+
+    /// Like [TokenMath::normalize_amount_from_any_utokens], but rounds with `rounding` instead of the configured
+    /// rounding and also returns the remainder truncated from `amount` (in utokens) before rounding.
+    fn normalize_amount_from_any_utokens_rounded(amount: impl Into<U256>, token_decimals: u8, rounding: Rounding) -> StdResult<(U256, U256)> {
+        let amount: U256 = amount.into();
+        let precision_diff = decimals_diff(token_decimals, Self::NORMALIZED_PRECISION)?;
+        if token_decimals <= Self::NORMALIZED_PRECISION {
+            Ok((checked_mul(amount, exp10(precision_diff))?, U256::ZERO))
+        } else {
+            scale_down_rounded(amount, precision_diff, rounding)
+        }
+    }
+
+    /// Like [TokenMath::denormalize_amount_to_any_utokens], but rounds with `rounding` instead of the configured
+    /// rounding and also returns the remainder truncated from `amount` (in normalized precision) before rounding.
+    fn denormalize_amount_to_any_utokens_rounded(amount: impl Into<U256>, token_decimals: u8, rounding: Rounding) -> StdResult<(U256, U256)> {
+        let normalized_amount: U256 = amount.into();
+        let precision_diff = decimals_diff(token_decimals, Self::NORMALIZED_PRECISION)?;
+        if token_decimals <= Self::NORMALIZED_PRECISION {
+            scale_down_rounded(normalized_amount, precision_diff, rounding)
+        } else {
+            Ok((checked_mul(normalized_amount, exp10(precision_diff))?, U256::ZERO))
+        }
+    }
+
+    /// Like [TokenMath::normalize_amount_to_any_token_precision], but rounds with `rounding` instead of the configured
+    /// rounding and also returns the remainder truncated from `amount` before rounding.
+    fn normalize_amount_to_any_token_precision_rounded(amount: impl Into<U256>, token_decimals: u8, rounding: Rounding) -> StdResult<(U256, U256)> {
+        let amount: U256 = amount.into();
+        let precision_diff = decimals_diff(token_decimals, Self::NORMALIZED_PRECISION)?;
+        if token_decimals >= Self::NORMALIZED_PRECISION {
+            Ok((amount, U256::ZERO))
+        } else {
+            let (scaled, remainder) = scale_down_rounded(amount, precision_diff, rounding)?;
+            Ok((checked_mul(scaled, exp10(precision_diff))?, remainder))
         }
     }
 
@@ -68,6 +97,21 @@ pub trait TokenMath {
     fn normalize_amount_to_token_precision(&self, amount: impl Into<U256>) -> StdResult<U256> {
         Self::normalize_amount_to_any_token_precision(amount, self.token_decimals())
     }
+
+    /// Amount (token decimal precision) -> Amount (normalized decimal precision) and truncated remainder (utokens).
+    fn normalize_amount_from_utokens_rounded(&self, amount: impl Into<U256>, rounding: Rounding) -> StdResult<(U256, U256)> {
+        Self::normalize_amount_from_any_utokens_rounded(amount, self.token_decimals(), rounding)
+    }
+
+    /// Amount (normalized decimal precision) -> Amount (token decimal precision) and truncated remainder (normalized).
+    fn denormalize_amount_to_utokens_rounded(&self, amount: impl Into<U256>, rounding: Rounding) -> StdResult<(U256, U256)> {
+        Self::denormalize_amount_to_any_utokens_rounded(amount, self.token_decimals(), rounding)
+    }
+
+    /// Amount (normalized decimal precision) -> Amount (normalized decimals, excess precision rounded) and truncated remainder.
+    fn normalize_amount_to_token_precision_rounded(&self, amount: impl Into<U256>, rounding: Rounding) -> StdResult<(U256, U256)> {
+        Self::normalize_amount_to_any_token_precision_rounded(amount, self.token_decimals(), rounding)
+    }
 }
 
 pub trait PriceMath {
@@ -97,4 +141,127 @@ pub trait PriceMath {
         muldiv(normalized_value, amount_precision, self.price())
     }
 
-}
\ No newline at end of file
+}
+
+/// Which side of a position a price values, deciding which end of a confidence interval is conservative.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub enum PriceSide {
+    /// Assets such as collateral are valued at `price - confidence`.
+    Collateral,
+    /// Liabilities such as debt are valued at `price + confidence`.
+    Debt,
+}
+
+/// [PriceMath] for feeds that publish a confidence interval around the price, such as Pyth.
+pub trait ConfidencePriceMath: PriceMath {
+    /// Half-width of the confidence interval, with the same precision as the price.
+    fn confidence(&self) -> U256;
+
+    /// The end of the confidence interval that is conservative for `side`. Collateral bottoms out at zero.
+    fn conservative_price(&self, side: PriceSide) -> StdResult<U256> {
+        match side {
+            PriceSide::Collateral => Ok(self.price().saturating_sub(self.confidence())),
+            PriceSide::Debt => checked_add(self.price(), self.confidence()),
+        }
+    }
+
+    /// Gets the value for some amount using the conservative price for `side`, rounding collateral down and debt up.
+    fn calc_conservative_value_from_amount(&self, amount: impl Into<U256> + Copy, side: PriceSide) -> StdResult<U256> {
+        let rounding = match side {
+            PriceSide::Collateral => Rounding::Down,
+            PriceSide::Debt => Rounding::Up,
+        };
+        muldiv_rounded(amount.into(), self.conservative_price(side)?, exp10(Self::PRICE_PRECISION), rounding)
+    }
+
+    /// Errors if the confidence interval is wider than `max_confidence_ratio` (an unsigned 60.18-decimal fraction) of
+    /// the price.
+    fn check_confidence(&self, max_confidence_ratio: impl Into<U256>) -> StdResult<()> {
+        let max_confidence_ratio: U256 = max_confidence_ratio.into();
+        let (price, confidence) = (self.price(), self.confidence());
+        // Rounding the ratio up keeps an interval just past the limit from passing.
+        if price == 0 || muldiv_up(confidence, UNIT, price)? > max_confidence_ratio {
+            return Err(StdError::generic_err(format!("Confidence {} is too wide for price {}", confidence, price)));
+        }
+        Ok(())
+    }
+}
+
+/// The result of [convert_amount].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ConvertedAmount {
+    /// Amount of the destination token, in its utokens.
+    pub amount: U256,
+    /// Source utokens whose value was rounded away, i.e. `amount` minus the source utokens needed to buy the converted
+    /// amount. Zero when the conversion is exact or rounds up.
+    pub dust: U256,
+}
+
+/// Converts `amount` utokens of a token with `from_decimals` decimals priced at `from_price` into utokens of a token with
+/// `to_decimals` decimals priced at `to_price`, rounding with `rounding`.
+///
+/// Both prices must share the same precision. The conversion is computed with a single full precision muldiv, so the
+/// only rounding is the one applied to the final result.
+pub fn convert_amount(
+    amount: impl Into<U256>,
+    from_decimals: u8,
+    from_price: impl Into<U256>,
+    to_decimals: u8,
+    to_price: impl Into<U256>,
+    rounding: Rounding,
+) -> StdResult<ConvertedAmount> {
+    let amount: U256 = amount.into();
+    let precision_diff = decimals_diff(to_decimals, from_decimals)?;
+    // amount * from_price * 10^to_decimals / (to_price * 10^from_decimals), with the common factor of ten cancelled.
+    let (numerator, denominator) = if to_decimals >= from_decimals {
+        (checked_mul(from_price.into(), exp10(precision_diff))?, to_price.into())
+    } else {
+        (from_price.into(), checked_mul(to_price.into(), exp10(precision_diff))?)
+    };
+    let converted = muldiv_rounded(amount, numerator, denominator, rounding)?;
+    let dust = if numerator == 0 {
+        amount
+    } else {
+        amount.saturating_sub(muldiv_up(converted, denominator, numerator)?)
+    };
+    Ok(ConvertedAmount { amount: converted, dust })
+}
+
+/// The difference between two decimal precisions, erroring if scaling by it would overflow a U256.
+fn decimals_diff(decimals: u8, other_decimals: u8) -> StdResult<u8> {
+    let precision_diff = decimals.abs_diff(other_decimals);
+    if precision_diff > 77 {
+        return Err(StdError::generic_err(format!("Decimals {} and {} are too far apart to scale between", decimals, other_decimals)));
+    }
+    Ok(precision_diff)
+}
+
+/// Divides `amount` by 10^`precision_diff`, rounding with [crate::common::bankers_round] or truncating.
+///
+/// Like `bankers_round`, only the first dropped digit decides the rounding, but the quotient is rounded instead of
+/// `amount`, so amounts near U256::MAX do not overflow.
+fn scale_down(amount: U256, precision_diff: u8, bankers_rounding: bool) -> StdResult<U256> {
+    let quotient = amount / exp10(precision_diff);
+    if precision_diff == 0 || !bankers_rounding {
+        return Ok(quotient);
+    }
+    let dropped = nth_digit(amount, precision_diff);
+    if dropped > 5 || (dropped == 5 && quotient % 2 != 0) {
+        checked_add(quotient, U256::ONE)
+    } else {
+        Ok(quotient)
+    }
+}
+
+/// Divides `amount` by 10^`precision_diff`, rounding with `rounding`, and returns the quotient and the remainder.
+fn scale_down_rounded(amount: U256, precision_diff: u8, rounding: Rounding) -> StdResult<(U256, U256)> {
+    let divisor = exp10(precision_diff);
+    let (quotient, remainder) = (amount / divisor, amount % divisor);
+    if rounding.rounds_up(quotient, remainder, divisor) {
+        Ok((checked_add(quotient, U256::ONE)?, remainder))
+    } else {
+        Ok((quotient, remainder))
+    }
+}
@@ -0,0 +1,200 @@
+//! ERC-4626 style vault accounting layered over [Rebase], where elastic is the vault's total assets and base is its
+//! total supply of shares. See https://eips.ethereum.org/EIPS/eip-4626.
+//!
+//! Following the spec, every conversion rounds in favor of the vault: shares minted for deposits and assets paid out
+//! for redemptions round down, while assets charged for mints and shares burned for withdrawals round up.
+//!
+//! Fees are charged like OpenZeppelin's `ERC4626Fees`. The entry fee is charged on top of the assets that back the
+//! minted shares and the exit fee on top of the assets withdrawn, and neither stays in the vault.
+
+use super::{Rebase, SimpleRebase, VirtualRebase};
+use crate::{
+    common::{checked_add, checked_sub, muldiv_up, Rounding},
+    ud60x18::UNIT,
+    U256,
+};
+use cosmwasm_std::StdResult;
+
+/// The result of a vault operation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct VaultAmounts {
+    /// Assets the user pays in or receives, including the fee charged on deposit and excluding it on withdraw.
+    pub assets: U256,
+    /// Shares minted to or burned from the user.
+    pub shares: U256,
+    /// Assets taken as a fee, which the caller should send to the fee recipient.
+    pub fee: U256,
+}
+
+pub trait Vault: Rebase {
+    /// Fee charged on deposits and mints as an unsigned 60.18-decimal fraction. Zero unless overridden.
+    fn entry_fee(&self) -> U256 {
+        U256::ZERO
+    }
+
+    /// Fee charged on withdrawals and redemptions as an unsigned 60.18-decimal fraction. Zero unless overridden.
+    fn exit_fee(&self) -> U256 {
+        U256::ZERO
+    }
+
+    /// Total assets managed by the vault.
+    fn total_assets(&self) -> U256 {
+        self.elastic()
+    }
+
+    /// Total supply of vault shares.
+    fn total_shares(&self) -> U256 {
+        self.base()
+    }
+
+    /// Shares the vault would exchange for `assets` in an ideal scenario, ignoring fees and rounding down.
+    fn convert_to_shares(&self, assets: impl Into<U256> + Copy) -> StdResult<U256> {
+        self.to_base(assets, Rounding::Down)
+    }
+
+    /// Assets the vault would exchange for `shares` in an ideal scenario, ignoring fees and rounding down.
+    fn convert_to_assets(&self, shares: impl Into<U256> + Copy) -> StdResult<U256> {
+        self.to_elastic(shares, Rounding::Down)
+    }
+
+    /// Maximum assets that can be deposited without overflowing the vault's totals.
+    fn max_deposit(&self) -> U256 {
+        U256::MAX - self.elastic()
+    }
+
+    /// Maximum shares that can be minted without overflowing the vault's totals.
+    fn max_mint(&self) -> U256 {
+        U256::MAX - self.base()
+    }
+
+    /// Maximum assets that an owner of `owner_shares` can withdraw.
+    fn max_withdraw(&self, owner_shares: impl Into<U256> + Copy) -> StdResult<U256> {
+        self.preview_redeem(owner_shares)
+    }
+
+    /// Maximum shares that an owner of `owner_shares` can redeem.
+    fn max_redeem(&self, owner_shares: impl Into<U256> + Copy) -> U256 {
+        owner_shares.into()
+    }
+
+    /// Shares minted for depositing `assets`, after the entry fee. Rounds down.
+    fn preview_deposit(&self, assets: impl Into<U256> + Copy) -> StdResult<U256> {
+        Ok(deposit_amounts(self, assets.into())?.shares)
+    }
+
+    /// Assets, including the entry fee, that must be deposited to mint `shares`. Rounds up.
+    fn preview_mint(&self, shares: impl Into<U256> + Copy) -> StdResult<U256> {
+        Ok(mint_amounts(self, shares.into())?.assets)
+    }
+
+    /// Shares burned to withdraw `assets` and pay the exit fee on them. Rounds up.
+    fn preview_withdraw(&self, assets: impl Into<U256> + Copy) -> StdResult<U256> {
+        Ok(withdraw_amounts(self, assets.into())?.shares)
+    }
+
+    /// Assets received for redeeming `shares`, after the exit fee. Rounds down.
+    fn preview_redeem(&self, shares: impl Into<U256> + Copy) -> StdResult<U256> {
+        Ok(redeem_amounts(self, shares.into())?.assets)
+    }
+
+    /// Deposits `assets` into the vault and mints shares for them.
+    fn deposit(&mut self, assets: impl Into<U256> + Copy) -> StdResult<VaultAmounts> {
+        let amounts = deposit_amounts(self, assets.into())?;
+        credit(self, amounts.assets - amounts.fee, amounts.shares)?;
+        Ok(amounts)
+    }
+
+    /// Mints exactly `shares`, charging the assets returned.
+    fn mint(&mut self, shares: impl Into<U256> + Copy) -> StdResult<VaultAmounts> {
+        let amounts = mint_amounts(self, shares.into())?;
+        credit(self, amounts.assets - amounts.fee, amounts.shares)?;
+        Ok(amounts)
+    }
+
+    /// Withdraws exactly `assets`, burning the shares returned.
+    fn withdraw(&mut self, assets: impl Into<U256> + Copy) -> StdResult<VaultAmounts> {
+        let amounts = withdraw_amounts(self, assets.into())?;
+        debit(self, amounts.assets + amounts.fee, amounts.shares)?;
+        Ok(amounts)
+    }
+
+    /// Burns `shares` and withdraws the assets returned.
+    fn redeem(&mut self, shares: impl Into<U256> + Copy) -> StdResult<VaultAmounts> {
+        let amounts = redeem_amounts(self, shares.into())?;
+        debit(self, amounts.assets + amounts.fee, amounts.shares)?;
+        Ok(amounts)
+    }
+}
+
+/// Fee to charge on top of `assets`, rounding up.
+pub fn fee_on_raw(assets: U256, fee: U256) -> StdResult<U256> {
+    muldiv_up(assets, fee, UNIT)
+}
+
+/// Fee included in `assets` that already has the fee added on top, rounding up.
+pub fn fee_on_total(assets: U256, fee: U256) -> StdResult<U256> {
+    muldiv_up(assets, fee, checked_add(fee, UNIT)?)
+}
+
+impl Vault for SimpleRebase {}
+
+impl Vault for VirtualRebase {}
+
+fn deposit_amounts<V: Vault + ?Sized>(vault: &V, assets: U256) -> StdResult<VaultAmounts> {
+    let fee = fee_on_total(assets, vault.entry_fee())?;
+    let shares = vault.to_base(assets - fee, Rounding::Down)?;
+    Ok(VaultAmounts {
+        assets,
+        shares,
+        fee,
+    })
+}
+
+fn mint_amounts<V: Vault + ?Sized>(vault: &V, shares: U256) -> StdResult<VaultAmounts> {
+    let assets = vault.to_elastic(shares, Rounding::Up)?;
+    let fee = fee_on_raw(assets, vault.entry_fee())?;
+    Ok(VaultAmounts {
+        assets: checked_add(assets, fee)?,
+        shares,
+        fee,
+    })
+}
+
+fn withdraw_amounts<V: Vault + ?Sized>(vault: &V, assets: U256) -> StdResult<VaultAmounts> {
+    let fee = fee_on_raw(assets, vault.exit_fee())?;
+    let shares = vault.to_base(checked_add(assets, fee)?, Rounding::Up)?;
+    Ok(VaultAmounts {
+        assets,
+        shares,
+        fee,
+    })
+}
+
+fn redeem_amounts<V: Vault + ?Sized>(vault: &V, shares: U256) -> StdResult<VaultAmounts> {
+    let assets = vault.to_elastic(shares, Rounding::Down)?;
+    let fee = fee_on_total(assets, vault.exit_fee())?;
+    Ok(VaultAmounts {
+        assets: assets - fee,
+        shares,
+        fee,
+    })
+}
+
+/// Adds to both totals, leaving the vault unchanged if either overflows.
+fn credit<V: Vault + ?Sized>(vault: &mut V, assets: U256, shares: U256) -> StdResult<()> {
+    let elastic = checked_add(vault.elastic(), assets)?;
+    let base = checked_add(vault.base(), shares)?;
+    vault.set_elastic(elastic);
+    vault.set_base(base);
+    Ok(())
+}
+
+/// Subtracts from both totals, leaving the vault unchanged if either underflows. Virtual offsets can make the shares
+/// exceed the real supply even when the assets are covered.
+fn debit<V: Vault + ?Sized>(vault: &mut V, assets: U256, shares: U256) -> StdResult<()> {
+    let elastic = checked_sub(vault.elastic(), assets)?;
+    let base = checked_sub(vault.base(), shares)?;
+    vault.set_elastic(elastic);
+    vault.set_base(base);
+    Ok(())
+}
@@ -0,0 +1,232 @@
+//! Time-weighted average prices built from cumulative price accumulators, like Uniswap V2's `price0CumulativeLast`.
+//!
+//! The accumulators add up `price * seconds` (and `log2(price) * seconds` for geometric averages) with wrapping
+//! arithmetic, so they never overflow: the difference between two snapshots is still exact as long as the true sum
+//! over the window fits in 256 bits. Prices are unsigned 60.18-decimal numbers and must be positive.
+
+use crate::{
+    ring_buffer::RingBuffer,
+    ud60x18::{self, UNIT, UNIT_SQUARED},
+    I256, U256,
+};
+use btr_macros::borsh_serde;
+use cosmwasm_std::{StdError, StdResult};
+
+/// How [TwapOracle::consult] averages prices over a window.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub enum TwapKind {
+    /// Mean of the prices weighted by time.
+    Arithmetic,
+    /// Geometric mean of the prices weighted by time, which is less sensitive to short-lived price spikes.
+    Geometric,
+}
+
+/// Cumulative sums at a point in time.
+#[borsh_serde]
+#[derive(Copy, Eq, Default)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct Observation {
+    /// Time of the observation in seconds.
+    pub timestamp: u64,
+    /// Wrapping sum of `price * seconds`.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub price_cumulative: U256,
+    /// Wrapping sum of `log2(price) * seconds`, as a signed 59.18-decimal number.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::I256Str"))]
+    pub log_price_cumulative: I256,
+}
+
+/// Accumulates the current price over time.
+#[borsh_serde]
+#[derive(Copy, Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct TwapAccumulator {
+    /// The price since `timestamp`.
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub price: U256,
+    /// Time of the last update in seconds.
+    pub timestamp: u64,
+    #[cfg_attr(feature = "abi", schemars(with = "crate::U256Str"))]
+    pub price_cumulative: U256,
+    #[cfg_attr(feature = "abi", schemars(with = "crate::I256Str"))]
+    pub log_price_cumulative: I256,
+}
+
+impl TwapAccumulator {
+    pub fn new(price: U256, timestamp: u64) -> StdResult<Self> {
+        signed_log2(price)?;
+        Ok(Self {
+            price,
+            timestamp,
+            price_cumulative: U256::ZERO,
+            log_price_cumulative: I256::ZERO,
+        })
+    }
+
+    /// Accumulates the current price up to `timestamp`, then sets the price to `price`.
+    pub fn update(&mut self, price: U256, timestamp: u64) -> StdResult<()> {
+        signed_log2(price)?;
+        let observation = self.observe(timestamp)?;
+        self.price_cumulative = observation.price_cumulative;
+        self.log_price_cumulative = observation.log_price_cumulative;
+        self.timestamp = timestamp;
+        self.price = price;
+        Ok(())
+    }
+
+    /// The cumulative sums at `timestamp`, which must not be before the last update, assuming the price is unchanged
+    /// until then.
+    pub fn observe(&self, timestamp: u64) -> StdResult<Observation> {
+        if timestamp < self.timestamp {
+            return Err(StdError::generic_err(format!(
+                "Timestamp {} is before the last update at {}",
+                timestamp, self.timestamp
+            )));
+        }
+        let elapsed = timestamp - self.timestamp;
+        Ok(Observation {
+            timestamp,
+            price_cumulative: self
+                .price_cumulative
+                .wrapping_add(self.price.wrapping_mul(U256::from(elapsed))),
+            log_price_cumulative: self
+                .log_price_cumulative
+                .wrapping_add(signed_log2(self.price)?.wrapping_mul(I256::from(elapsed))),
+        })
+    }
+}
+
+/// A [TwapAccumulator] with a ring buffer of past [Observation]s to consult TWAPs over a window.
+#[borsh_serde]
+#[derive(Eq)]
+#[cfg_attr(feature = "abi", derive(schemars::JsonSchema))]
+pub struct TwapOracle {
+    pub accumulator: TwapAccumulator,
+    /// Recording past the capacity overwrites the oldest observation.
+    observations: RingBuffer<Observation>,
+}
+
+impl TwapOracle {
+    pub fn new(price: U256, timestamp: u64, capacity: u32) -> StdResult<Self> {
+        let accumulator = TwapAccumulator::new(price, timestamp)?;
+        let mut observations = RingBuffer::new(capacity)?;
+        observations.push(accumulator.observe(timestamp)?);
+        Ok(Self {
+            accumulator,
+            observations,
+        })
+    }
+
+    /// Maximum number of observations kept.
+    pub fn capacity(&self) -> u32 {
+        self.observations.capacity()
+    }
+
+    /// Iterates over the observations from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &Observation> {
+        self.observations.iter()
+    }
+
+    /// Sets the price to `price` from `timestamp` on and records an observation. An update at the same time as the
+    /// latest observation replaces its price.
+    pub fn update(&mut self, price: U256, timestamp: u64) -> StdResult<()> {
+        self.accumulator.update(price, timestamp)?;
+        let observation = self.accumulator.observe(timestamp)?;
+        match self.observations.latest_mut() {
+            Some(latest) if latest.timestamp == timestamp => *latest = observation,
+            _ => self.observations.push(observation),
+        }
+        Ok(())
+    }
+
+    /// The time-weighted average price over the `window` seconds up to `now`, which must not be before the last update.
+    /// Errors if the oldest observation is younger than the window.
+    pub fn consult(&self, now: u64, window: u64, kind: TwapKind) -> StdResult<U256> {
+        if window == 0 {
+            return Err(StdError::generic_err("TWAP window must be positive"));
+        }
+        let start = now.checked_sub(window).ok_or_else(|| {
+            StdError::generic_err(format!("TWAP window {} is before time 0", window))
+        })?;
+        let end = self.accumulator.observe(now)?;
+        let start = self.observe_at(start)?;
+
+        let elapsed = U256::from(window);
+        match kind {
+            TwapKind::Arithmetic => {
+                Ok(end.price_cumulative.wrapping_sub(start.price_cumulative) / elapsed)
+            }
+            TwapKind::Geometric => {
+                let log = end
+                    .log_price_cumulative
+                    .wrapping_sub(start.log_price_cumulative)
+                    / I256::from(window);
+                signed_exp2(log)
+            }
+        }
+    }
+
+    /// The cumulative sums at `timestamp`, interpolated between the observations around it.
+    fn observe_at(&self, timestamp: u64) -> StdResult<Observation> {
+        let mut observations = self.iter().peekable();
+        while let Some(observation) = observations.next() {
+            if observation.timestamp > timestamp {
+                break;
+            }
+            return match observations.peek() {
+                Some(next) if next.timestamp <= timestamp => continue,
+                // The price was constant between two observations, so interpolating between them is exact.
+                Some(next) => {
+                    let (elapsed, span) = (
+                        timestamp - observation.timestamp,
+                        next.timestamp - observation.timestamp,
+                    );
+                    Ok(Observation {
+                        timestamp,
+                        price_cumulative: observation.price_cumulative.wrapping_add(
+                            next.price_cumulative
+                                .wrapping_sub(observation.price_cumulative)
+                                / U256::from(span)
+                                * U256::from(elapsed),
+                        ),
+                        log_price_cumulative: observation.log_price_cumulative.wrapping_add(
+                            next.log_price_cumulative
+                                .wrapping_sub(observation.log_price_cumulative)
+                                / I256::from(span)
+                                * I256::from(elapsed),
+                        ),
+                    })
+                }
+                None => self.accumulator.observe(timestamp),
+            };
+        }
+        Err(StdError::not_found(format!(
+            "TWAP observation at {}",
+            timestamp
+        )))
+    }
+}
+
+/// log2 of an unsigned 60.18-decimal number as a signed 59.18-decimal number, using [ud60x18::log2] on the inverse of
+/// numbers below one.
+fn signed_log2(x: U256) -> StdResult<I256> {
+    if x == 0 {
+        return Err(StdError::generic_err("TWAP prices must be positive"));
+    }
+    if x >= UNIT {
+        Ok(ud60x18::log2(x)?.as_i256())
+    } else {
+        Ok(-ud60x18::log2(UNIT_SQUARED / x)?.as_i256())
+    }
+}
+
+/// The inverse of [signed_log2], using [ud60x18::exp2].
+fn signed_exp2(x: I256) -> StdResult<U256> {
+    if x >= 0 {
+        ud60x18::exp2(x.as_u256())
+    } else {
+        Ok(UNIT_SQUARED / ud60x18::exp2(x.unsigned_abs())?)
+    }
+}
@@ -2,11 +2,13 @@
 //!
 //!
 pub mod constants;
+mod types;
 
-use super::{asm::Asm, common, tens::*};
-use crate::common::{msb, muldiv, muldiv18};
+use super::{asm::Asm, common, decimal, tens::*};
+use crate::common::{msb, muldiv, muldiv18, Rounding};
 pub use constants::*;
-use cosmwasm_std::{DivideByZeroError, StdError, StdResult};
+use cosmwasm_std::{Decimal, Decimal256, DivideByZeroError, StdError, StdResult};
+pub use types::*;
 use ethnum::{AsU256, U256};
 
 #[derive(thiserror::Error, Debug)]
@@ -29,6 +31,8 @@ pub enum UD60x18Error {
     SubUnderflow(U256, U256),
     #[error("UD60x18 Trunc overflow: {0}")]
     ToUD60x18Overflow(U256),
+    #[error("UD60x18 Decimal overflow: {0}")]
+    DecimalOverflow(U256),
 }
 
 #[allow(clippy::from_over_into)]
@@ -144,6 +148,61 @@ pub fn from_ud60x18(x: U256) -> U256 {
     x / UNIT
 }
 
+/// Parses a base-10 string such as `"1.25"` or `"3e-4"` into an unsigned 60.18-decimal fixed-point number.
+///
+/// Requirements:
+/// - The string must not have more than 18 decimals; see [parse_decimal_rounded] to round them away instead.
+/// - The value must not be negative and must fit within MAX_UD60x18.
+pub fn parse_decimal(s: &str) -> StdResult<U256> {
+    parse_with(s, None)
+}
+
+/// Same as [parse_decimal], but rounds digits beyond the 18th decimal using `rounding`.
+pub fn parse_decimal_rounded(s: &str, rounding: Rounding) -> StdResult<U256> {
+    parse_with(s, Some(rounding))
+}
+
+fn parse_with(s: &str, rounding: Option<Rounding>) -> StdResult<U256> {
+    let (negative, x) = decimal::parse(s, rounding).map_err(Into::<StdError>::into)?;
+    if negative && x != 0 {
+        return Err(decimal::DecimalError::Negative(s.to_string()).into());
+    }
+    Ok(x)
+}
+
+/// Formats an unsigned 60.18-decimal fixed-point number as a base-10 string with at most `max_decimals` decimals.
+///
+/// Excess decimals are truncated and trailing zeros are trimmed, so 1.250 formats as `"1.25"` and 2.0 as `"2"`.
+pub fn format_decimal(x: U256, max_decimals: u8) -> String {
+    decimal::format(x, max_decimals)
+}
+
+/// Converts a [Decimal256] into an unsigned 60.18-decimal fixed-point number. Both have 18 decimals, so this is lossless.
+pub fn from_decimal256(x: Decimal256) -> U256 {
+    x.atomics().into()
+}
+
+/// Converts an unsigned 60.18-decimal fixed-point number into a [Decimal256]. Both have 18 decimals, so this is lossless.
+pub fn to_decimal256(x: U256) -> Decimal256 {
+    Decimal256::new(x.into())
+}
+
+/// Converts a [Decimal] into an unsigned 60.18-decimal fixed-point number. Both have 18 decimals, so this is lossless.
+pub fn from_decimal(x: Decimal) -> U256 {
+    U256::new(x.atomics().u128())
+}
+
+/// Converts an unsigned 60.18-decimal fixed-point number into a [Decimal].
+///
+/// Requirements:
+/// - x must fit within Decimal::MAX (u128::MAX atomics).
+pub fn to_decimal(x: U256) -> StdResult<Decimal> {
+    if x > u128::MAX {
+        return Err(UD60x18Error::DecimalOverflow(x).into());
+    }
+    Ok(Decimal::new(x.as_u128().into()))
+}
+
 /// Performs x * (y / z) where x, y, z are all 60.18-decimal fixed-point numbers.
 pub fn mul_ratio(x: U256, y: U256, z: U256) -> StdResult<U256> {
     let ratio = div(y, z)?;
@@ -0,0 +1,129 @@
+use super::{
+    format_decimal, from_decimal, from_decimal256, parse_decimal, to_decimal, to_decimal256, UNIT,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use cosmwasm_std::{Decimal, Decimal256, StdError};
+use ethnum::U256;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// An unsigned 60.18-decimal fixed-point number, i.e. a [U256] scaled by 1e18.
+///
+/// Serializes to JSON as a decimal string (`"1.25"`) like [Decimal256], and to Borsh as the underlying [U256].
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+)]
+pub struct UD60x18(pub U256);
+
+impl UD60x18 {
+    pub const ZERO: Self = Self(U256::ZERO);
+    pub const ONE: Self = Self(UNIT);
+    pub const MAX: Self = Self(U256::MAX);
+
+    /// Creates a value from its raw 18-decimal representation.
+    pub const fn new(x: U256) -> Self {
+        Self(x)
+    }
+
+    /// Gets the raw 18-decimal representation.
+    pub const fn raw(&self) -> U256 {
+        self.0
+    }
+}
+
+impl From<U256> for UD60x18 {
+    fn from(x: U256) -> Self {
+        Self(x)
+    }
+}
+
+impl From<UD60x18> for U256 {
+    fn from(x: UD60x18) -> Self {
+        x.0
+    }
+}
+
+impl From<Decimal256> for UD60x18 {
+    fn from(x: Decimal256) -> Self {
+        Self(from_decimal256(x))
+    }
+}
+
+impl From<UD60x18> for Decimal256 {
+    fn from(x: UD60x18) -> Self {
+        to_decimal256(x.0)
+    }
+}
+
+impl From<Decimal> for UD60x18 {
+    fn from(x: Decimal) -> Self {
+        Self(from_decimal(x))
+    }
+}
+
+impl TryFrom<UD60x18> for Decimal {
+    type Error = StdError;
+
+    fn try_from(x: UD60x18) -> Result<Self, Self::Error> {
+        to_decimal(x.0)
+    }
+}
+
+impl fmt::Display for UD60x18 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format_decimal(self.0, 18))
+    }
+}
+
+impl FromStr for UD60x18 {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_decimal(s).map(Self)
+    }
+}
+
+impl Serialize for UD60x18 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for UD60x18 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|err: StdError| serde::de::Error::custom(err.to_string()))
+    }
+}
+
+#[cfg(feature = "abi")]
+impl schemars::JsonSchema for UD60x18 {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+}